@@ -186,6 +186,27 @@ impl InstDefParsed {
         }
     }
 
+    pub fn impl_from_opcode_name_clause(&self) -> TokenStream {
+        let id = &self.id;
+        match &self.opcode {
+            OpcodeDefParsed::LocalDef { type_name } => {
+                quote! {
+                    if let Some(opcode) = #type_name::from_opcode_name(name) {
+                        return Some(PMachineOpcode::#id(opcode));
+                    }
+                }
+            }
+            OpcodeDefParsed::LiteralDef { name, .. } => {
+                let name = name.value();
+                quote! {
+                    if name.eq_ignore_ascii_case(#name) {
+                        return Some(PMachineOpcode::#id);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn impl_opcode_byte_clause(&self) -> TokenStream {
         let id = &self.id;
         match &self.opcode {
@@ -298,6 +319,47 @@ impl InstDefParsed {
         }
     }
 
+    pub fn impl_args_and_types_clause(&self) -> TokenStream {
+        let id = &self.id;
+        let args = NamesList::from_iter("arg", self.arg_types.iter());
+        let arg_names = args.name_iter();
+        let arg_types = self.arg_types.iter().map(ArgType::arg_type_value);
+        let arg_pairs = quote! { vec![#((#arg_names, #arg_types)),*] };
+        let arg_names = args.name_iter();
+        match &self.opcode {
+            OpcodeDefParsed::LocalDef { .. } => {
+                quote! {
+                    PMachineInst::#id(_, #(#arg_names),*) => #arg_pairs,
+                }
+            }
+            OpcodeDefParsed::LiteralDef { .. } => {
+                quote! {
+                    PMachineInst::#id(#(#arg_names),*) => #arg_pairs,
+                }
+            }
+        }
+    }
+
+    pub fn impl_decode_clause(&self) -> TokenStream {
+        let id = &self.id;
+        let read_args = self.arg_types.iter().map(|arg_type| {
+            let arg_type = arg_type.arg_type_value();
+            quote! { Arg::read_arg(#arg_type, arg_width, &mut buf)? }
+        });
+        match &self.opcode {
+            OpcodeDefParsed::LocalDef { .. } => {
+                quote! {
+                    PMachineOpcode::#id(opcode) => PMachineInst::#id(opcode, #(#read_args),*),
+                }
+            }
+            OpcodeDefParsed::LiteralDef { .. } => {
+                quote! {
+                    PMachineOpcode::#id => PMachineInst::#id(#(#read_args),*),
+                }
+            }
+        }
+    }
+
     pub fn impl_asm_write_inst_clause(&self, end_of_inst_var: &syn::Ident) -> TokenStream {
         let id = &self.id;
         let args = NamesList::from_iter("arg", self.arg_types.iter());
@@ -379,6 +441,7 @@ impl InstDefListParsed {
         let opcode_enum_items = self.inst_defs.iter().map(InstDefParsed::opcode_enum_item);
         let inst_enum_items = self.inst_defs.iter().map(InstDefParsed::inst_enum_item);
         let from_opcode_byte_impl = self.impl_from_opcode_byte();
+        let from_opcode_name_impl = self.impl_from_opcode_name();
         let opcode_byte_impl = self.impl_opcode_byte();
         let opcode_name_impl = self.impl_opcode_name();
         let opcode_impl = self.impl_opcode(&inst_type_name);
@@ -386,6 +449,8 @@ impl InstDefListParsed {
         let inst_size_impl = self.impl_inst_size(&inst_type_name);
         let asm_inst_size_impl = self.impl_inst_size(&asm_inst_type_name);
         let write_inst_impl = self.impl_write_inst();
+        let encode_impl = self.impl_encode();
+        let decode_impl = self.impl_decode();
         let asm_write_inst_impl = self.impl_asm_write_inst();
         let asm_inst_enum_items = self.inst_defs.iter().map(|inst| inst.asm_inst_enum_item());
         quote! {
@@ -396,10 +461,20 @@ impl InstDefListParsed {
 
             impl Opcode for PMachineOpcode {
                 #from_opcode_byte_impl
+                #from_opcode_name_impl
                 #opcode_byte_impl
                 #opcode_name_impl
             }
 
+            impl std::str::FromStr for PMachineOpcode {
+                type Err = anyhow::Error;
+
+                fn from_str(name: &str) -> anyhow::Result<Self> {
+                    PMachineOpcode::from_opcode_name(name)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown opcode name: {:?}", name))
+                }
+            }
+
             #[derive(Clone, Copy, Debug)]
             pub enum PMachineInst {
                 #(#inst_enum_items),*
@@ -415,6 +490,11 @@ impl InstDefListParsed {
                 #write_inst_impl
             }
 
+            impl PMachineInst {
+                #encode_impl
+                #decode_impl
+            }
+
             #[derive(Clone, Debug)]
             pub enum PMachineAsmInst {
                 #(#asm_inst_enum_items),*
@@ -445,6 +525,19 @@ impl InstDefListParsed {
         }
     }
 
+    fn impl_from_opcode_name(&self) -> TokenStream {
+        let from_opcode_name_clauses = self
+            .inst_defs
+            .iter()
+            .map(InstDefParsed::impl_from_opcode_name_clause);
+        quote! {
+            fn from_opcode_name(name: &str) -> Option<Self> {
+                #(#from_opcode_name_clauses)*
+                None
+            }
+        }
+    }
+
     fn impl_opcode_byte(&self) -> TokenStream {
         let opcode_enum_items = self
             .inst_defs
@@ -516,6 +609,68 @@ impl InstDefListParsed {
         }
     }
 
+    fn impl_encode(&self) -> TokenStream {
+        let args_and_types_clauses = self
+            .inst_defs
+            .iter()
+            .map(InstDefParsed::impl_args_and_types_clause);
+        quote! {
+            /// Returns each argument of the instruction, paired with the
+            /// argument type the instruction expects in that position.
+            pub fn args_and_types(&self) -> Vec<(&Arg, ArgType)> {
+                match self {
+                    #(#args_and_types_clauses)*
+                }
+            }
+
+            /// Encodes the instruction, including the opcode byte.
+            ///
+            /// The byte argument form is used if every argument fits in it,
+            /// otherwise the word form is used. Returns an error if an
+            /// argument's type does not match the instruction definition.
+            pub fn encode(&self, out: &mut Vec<u8>) -> anyhow::Result<()> {
+                let args_and_types = self.args_and_types();
+                for (i, (arg, expected)) in args_and_types.iter().enumerate() {
+                    anyhow::ensure!(
+                        arg.arg_type() == *expected,
+                        "Argument {} of {} has type {:?}, expected {:?}",
+                        i,
+                        self.opcode().opcode_name(),
+                        arg.arg_type(),
+                        expected
+                    );
+                }
+                let arg_width = if args_and_types
+                    .iter()
+                    .all(|(arg, _)| arg.fits_width(ArgsWidth::Byte))
+                {
+                    ArgsWidth::Byte
+                } else {
+                    ArgsWidth::Word
+                };
+                self.write_inst(arg_width, out)
+            }
+        }
+    }
+
+    fn impl_decode(&self) -> TokenStream {
+        let decode_clauses = self.inst_defs.iter().map(InstDefParsed::impl_decode_clause);
+        quote! {
+            /// Decodes a single instruction, including the opcode byte. This
+            /// is the inverse of `encode`.
+            pub fn decode<R: std::io::Read>(mut buf: R) -> anyhow::Result<Self> {
+                let opcode_byte = read_byte(&mut buf)?;
+                let arg_width = if opcode_byte & 0x01 == 0 { ArgsWidth::Word } else { ArgsWidth::Byte };
+                let Some(opcode) = PMachineOpcode::from_opcode_byte(opcode_byte)? else {
+                    anyhow::bail!("Unknown opcode byte: 0x{:02X}", opcode_byte);
+                };
+                Ok(match opcode {
+                    #(#decode_clauses)*
+                })
+            }
+        }
+    }
+
     fn impl_asm_write_inst(&self) -> TokenStream {
         let end_of_inst_var = syn::Ident::new("end_of_inst", Span::call_site());
         let asm_write_inst_clauses = self
@@ -43,13 +43,13 @@ impl ArgValue {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Signedness {
     Signed,
     Unsigned,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ArgType {
     Byte,
     Word,
@@ -120,6 +120,33 @@ pub struct Arg {
 }
 
 impl Arg {
+    pub fn new(arg_type: ArgType, value: u16) -> Self {
+        Self { arg_type, value }
+    }
+
+    pub fn arg_type(&self) -> ArgType {
+        self.arg_type
+    }
+
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    /// Returns true if the value can be written with the given instruction
+    /// argument width without losing information.
+    pub fn fits_width(&self, inst_args_width: ArgsWidth) -> bool {
+        match (self.arg_type, inst_args_width) {
+            (ArgType::Byte, _) => safe_unsigned_narrow(self.value).is_ok(),
+            (ArgType::Word, _) | (ArgType::VarWord(_), ArgsWidth::Word) => true,
+            (ArgType::VarWord(Signedness::Signed), ArgsWidth::Byte) => {
+                safe_signed_narrow(self.value).is_ok()
+            }
+            (ArgType::VarWord(Signedness::Unsigned), ArgsWidth::Byte) => {
+                safe_unsigned_narrow(self.value).is_ok()
+            }
+        }
+    }
+
     pub fn read_arg<R: std::io::Read>(
         arg_type: ArgType,
         inst_args_width: ArgsWidth,
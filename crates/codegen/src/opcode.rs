@@ -10,6 +10,9 @@ pub trait Opcode: Sized {
     /// Returns the opcode for this instruction. This ignores the low bit of the
     /// opcode byte, which is the arg width.
     fn from_opcode_byte(opcode: u8) -> anyhow::Result<Option<Self>>;
+    /// Returns the opcode with the given name, ignoring case. This is the
+    /// inverse of `opcode_name`.
+    fn from_opcode_name(name: &str) -> Option<Self>;
     /// Returns the opcode byte for this instruction. The low bit is not set.
     fn opcode_byte(&self) -> u8;
     // Returns the name of the opcode.
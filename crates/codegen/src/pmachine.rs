@@ -9,7 +9,7 @@ use crate::{
     insts::{AsmInst, Inst, InstBase},
     opcode::Opcode,
 };
-use sci_utils::numbers::write_byte;
+use sci_utils::numbers::{read_byte, write_byte};
 use sci_utils::reloc_buffer::writer::RelocWriter;
 use sci_utils::symbol::Symbol;
 use var_access::VarAccessOp;
@@ -76,3 +76,92 @@ sci_codegen_macros::define_insts! {
     PUSHSELF("pushSelf", 0x3E, ());
     VARACCESS(VarAccessOp, (VarUWord));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(inst: PMachineInst) -> (Vec<u8>, PMachineInst) {
+        let mut bytes = Vec::new();
+        inst.encode(&mut bytes).unwrap();
+        let decoded = PMachineInst::decode(bytes.as_slice()).unwrap();
+        (bytes, decoded)
+    }
+
+    fn args(inst: &PMachineInst) -> Vec<(ArgType, u16)> {
+        inst.args_and_types()
+            .into_iter()
+            .map(|(arg, _)| (arg.arg_type(), arg.value()))
+            .collect()
+    }
+
+    #[test]
+    fn encode_uses_byte_form_for_small_args() {
+        let inst = PMachineInst::CALLK(
+            Arg::new(ArgType::VarWord(Signedness::Unsigned), 0x42),
+            Arg::new(ArgType::Byte, 4),
+        );
+        let (bytes, decoded) = round_trip(inst);
+        assert_eq!(bytes, vec![0x43, 0x42, 0x04]);
+        assert!(matches!(decoded, PMachineInst::CALLK(..)));
+        assert_eq!(args(&decoded), args(&inst));
+    }
+
+    #[test]
+    fn encode_uses_word_form_for_large_args() {
+        let inst = PMachineInst::CALLE(
+            Arg::new(ArgType::VarWord(Signedness::Unsigned), 0x1234),
+            Arg::new(ArgType::VarWord(Signedness::Unsigned), 1),
+            Arg::new(ArgType::Byte, 2),
+        );
+        let (bytes, decoded) = round_trip(inst);
+        assert_eq!(bytes, vec![0x46, 0x34, 0x12, 0x01, 0x00, 0x02]);
+        assert!(matches!(decoded, PMachineInst::CALLE(..)));
+        assert_eq!(args(&decoded), args(&inst));
+    }
+
+    #[test]
+    fn encode_sign_extends_negative_args() {
+        let inst = PMachineInst::LDI(Arg::new(ArgType::VarWord(Signedness::Signed), 0xFFFE));
+        let (bytes, decoded) = round_trip(inst);
+        assert_eq!(bytes, vec![0x35, 0xFE]);
+        assert_eq!(args(&decoded), args(&inst));
+    }
+
+    #[test]
+    fn encode_round_trips_local_opcodes() {
+        let opcode = VarAccessOp::from_opcode_byte(0x87).unwrap().unwrap();
+        let inst =
+            PMachineInst::VARACCESS(opcode, Arg::new(ArgType::VarWord(Signedness::Unsigned), 3));
+        let (bytes, decoded) = round_trip(inst);
+        assert_eq!(bytes, vec![0x87, 0x03]);
+        assert_eq!(decoded.opcode().opcode_byte(), opcode.opcode_byte());
+        assert_eq!(args(&decoded), args(&inst));
+    }
+
+    #[test]
+    fn encode_rejects_mismatched_arg_types() {
+        let inst = PMachineInst::SEND(Arg::new(ArgType::Word, 4));
+        assert!(inst.encode(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn opcodes_parse_from_names() {
+        let parse = |name: &str| name.parse::<PMachineOpcode>().map(|op| op.opcode_byte());
+        assert_eq!(parse("pushi").unwrap(), 0x38);
+        assert_eq!(parse("PUSHI").unwrap(), 0x38);
+        assert_eq!(parse("selfID").unwrap(), 0x5C);
+        assert_eq!(parse("self").unwrap(), 0x54);
+        assert_eq!(parse("lsli").unwrap(), 0x9A);
+        assert!(parse("frobnicate").is_err());
+        assert!(PMachineOpcode::from_opcode_name("").is_none());
+
+        // Every opcode's name parses back to the same opcode.
+        for byte in (0..=0xFF).step_by(2) {
+            let Some(opcode) = PMachineOpcode::from_opcode_byte(byte).unwrap() else {
+                continue;
+            };
+            assert_eq!(parse(&opcode.opcode_name()).unwrap(), byte);
+        }
+    }
+}
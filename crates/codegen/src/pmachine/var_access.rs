@@ -72,6 +72,14 @@ impl Opcode for VarAccessOp {
         }))
     }
 
+    fn from_opcode_name(name: &str) -> Option<Self> {
+        // There are only 64 opcodes in the family, so search all of them.
+        (0x80..=0xFF)
+            .step_by(2)
+            .filter_map(|byte| Self::from_opcode_byte(byte).ok().flatten())
+            .find(|opcode| opcode.opcode_name().eq_ignore_ascii_case(name))
+    }
+
     fn opcode_byte(&self) -> u8 {
         let var_type_bits: u8 = match self.var_type {
             VarType::Global => 0b00,
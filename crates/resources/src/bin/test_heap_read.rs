@@ -1,6 +1,6 @@
 use sci_resources::{
     file::open_game_resources,
-    types::{class_species::ClassSpeciesTable, script::load_script, selector_table::SelectorTable},
+    types::{class_species::ClassSpeciesTable, script::load_script},
     ResourceId, ResourceType,
 };
 use sci_utils::buffer::Buffer;
@@ -20,13 +20,6 @@ fn main() {
 
     println!("Species Table: {:#?}", species_table);
 
-    let selector_table_resource = resources
-        .get_resource(&ResourceId::new(ResourceType::Vocab, 997))
-        .unwrap();
-
-    let selector_table =
-        SelectorTable::load_from(selector_table_resource.load_data().unwrap().narrow()).unwrap();
-
     for script_res in resources.resources_of_type(sci_resources::ResourceType::Script) {
         println!("Script Id: {:?}", script_res.id());
         let resource_id = sci_resources::ResourceId::new(
@@ -35,7 +28,6 @@ fn main() {
         );
         let heap_res = resources.get_resource(&resource_id).unwrap();
         let _loaded_script = load_script(
-            &selector_table,
             &script_res.load_data().unwrap().narrow(),
             &heap_res.load_data().unwrap().narrow(),
         )
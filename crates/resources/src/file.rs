@@ -2,53 +2,168 @@ use std::{
     collections::{btree_map, BTreeMap},
     fs::File,
     io,
-    path::Path,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 
+use audio::{AudioMap, AudioMapFormat, AudioVolume, AUDIO_MAP_NUM};
+use cache::{cached_lazy_block, BlockCache};
 use data::DataFile;
 
 use sci_utils::block::{Block, BlockReader, BlockSource, LazyBlock};
 
 use super::{ResourceId, ResourceType};
 
+mod audio;
+mod cache;
 mod data;
 mod map;
+mod patch;
 
+pub use patch::{patch_file_name, read_patches, write_new_patch, write_patch};
+
+/// Reads the resources listed in a map file from its data file.
+///
+/// A resource whose entry in the data file cannot be read is still included,
+/// so that the rest of the game can be used. Loading it returns the error,
+/// and [`ResourceSet::check_integrity`] reports it.
 pub fn read_resources(map_file: &Path, data_file: &Path) -> io::Result<ResourceSet> {
     let map_file = Block::from_reader(File::open(map_file)?)?;
-    let data_file = DataFile::new(BlockSource::from_path(data_file)?);
+    let data_path = data_file;
+    let data_file = DataFile::new(BlockSource::from_path(data_path)?);
     let resource_locations = map::ResourceLocations::read_from(BlockReader::new(map_file))?;
 
     let mut entries = BTreeMap::new();
+    let mut locations = BTreeMap::new();
 
     for location in resource_locations.locations() {
-        let block = data_file.read_contents(&location)?;
-        if block.id() != &location.id {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!(
-                    "Resource ID mismatch: expected {:?}, got {:?}",
-                    location.id,
-                    block.id()
-                ),
-            ));
-        }
-        entries.insert(location.id, block.data().clone());
+        let contents = data_file
+            .read_raw_contents(&location)
+            .and_then(|raw_contents| {
+                let packed_size = raw_contents.packed_size();
+                Ok((packed_size, data::Contents::try_from(raw_contents)?))
+            })
+            .and_then(|(packed_size, block)| {
+                if block.id() != &location.id {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Resource ID mismatch: expected {:?}, got {:?}",
+                            location.id,
+                            block.id()
+                        ),
+                    ));
+                }
+                Ok((packed_size, block))
+            });
+        let (data, packing) = match contents {
+            Ok((packed_size, block)) => (
+                block.data().clone(),
+                Some(Packing {
+                    compression_type: block.original_compression(),
+                    packed_size,
+                    unpacked_size: block.unpacked_size(),
+                }),
+            ),
+            Err(err) => {
+                let (kind, message) = (err.kind(), err.to_string());
+                (
+                    LazyBlock::from_factory(move || {
+                        Err(io::Error::new(kind, message.clone()).into())
+                    }),
+                    None,
+                )
+            }
+        };
+        entries.insert(location.id, data);
+        locations.insert(
+            location.id,
+            VolumeLocation {
+                volume: data_path.to_path_buf(),
+                offset: location.file_offset as u64,
+                packing,
+            },
+        );
     }
 
-    Ok(ResourceSet { entries })
+    Ok(ResourceSet { entries, locations })
+}
+
+/// Reads the `Audio` resources from an external audio volume, using the
+/// contents of the audio map resource to locate them.
+pub fn read_audio_resources(audio_map: Block, volume_file: &Path) -> io::Result<ResourceSet> {
+    let format = AudioMapFormat::detect(audio_map.as_slice());
+    let audio_map = AudioMap::read_from(BlockReader::new(audio_map), format)?;
+    let volume = AudioVolume::new(BlockSource::from_path(volume_file)?);
+    let locations = audio_map
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                ResourceId::new(ResourceType::Audio, entry.resource_num),
+                VolumeLocation {
+                    volume: volume_file.to_path_buf(),
+                    offset: entry.file_offset as u64,
+                    packing: None,
+                },
+            )
+        })
+        .collect();
+    Ok(ResourceSet {
+        entries: volume.read_contents(&audio_map)?.collect(),
+        locations,
+    })
+}
+
+/// The physical location of a resource on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeLocation {
+    /// The path of the volume file containing the resource.
+    pub volume: PathBuf,
+    /// The byte offset of the resource's entry within the volume file.
+    pub offset: u64,
+    /// How the resource is stored in the volume, if its entry has a header
+    /// describing it.
+    pub packing: Option<Packing>,
+}
+
+/// The storage details recorded in a resource's entry header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packing {
+    /// The compression method used to store the resource (0 for none).
+    pub compression_type: u16,
+    /// The size of the resource as stored in the volume.
+    pub packed_size: u16,
+    /// The size of the resource once decompressed.
+    pub unpacked_size: u16,
+}
+
+/// A problem with a single resource, found by
+/// [`ResourceSet::check_integrity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityProblem {
+    pub id: ResourceId,
+    pub reason: String,
 }
 
 pub struct ResourceSet {
     pub entries: BTreeMap<ResourceId, LazyBlock>,
+    /// The on-disk locations of the resources that were read from volume
+    /// files. Resources created in memory have no location.
+    pub locations: BTreeMap<ResourceId, VolumeLocation>,
 }
 
 impl ResourceSet {
-    pub fn get_resource(&self, id: &ResourceId) -> Option<Resource> {
-        self.entries.get(id).map(|b| Resource {
+    fn make_resource(&self, id: &ResourceId, source: &LazyBlock) -> Resource {
+        Resource {
             id: *id,
-            source: b.clone(),
-        })
+            source: source.clone(),
+            location: self.locations.get(id).cloned(),
+        }
+    }
+
+    pub fn get_resource(&self, id: &ResourceId) -> Option<Resource> {
+        self.entries.get(id).map(|b| self.make_resource(id, b))
     }
 
     pub fn resource_ids(&self) -> impl Iterator<Item = ResourceId> + '_ {
@@ -56,10 +171,9 @@ impl ResourceSet {
     }
 
     pub fn resources(&self) -> impl Iterator<Item = Resource> + '_ {
-        self.entries.iter().map(|(id, block)| Resource {
-            id: *id,
-            source: block.clone(),
-        })
+        self.entries
+            .iter()
+            .map(|(id, block)| self.make_resource(id, block))
     }
 
     pub fn resources_of_type(&self, type_id: ResourceType) -> impl Iterator<Item = Resource> + '_ {
@@ -67,27 +181,105 @@ impl ResourceSet {
             if id.type_id != type_id {
                 return None;
             }
-            Some(Resource {
-                id: *id,
-                source: block.clone(),
-            })
+            Some(self.make_resource(id, block))
         })
     }
 
     pub fn with_overlay(&self, overlay: &ResourceSet) -> ResourceSet {
         let mut entries = self.entries.clone();
+        let mut locations = self.locations.clone();
         for (id, block) in overlay.entries.iter() {
             entries.insert(*id, block.clone());
+            match overlay.locations.get(id) {
+                Some(location) => locations.insert(*id, location.clone()),
+                None => locations.remove(id),
+            };
+        }
+        ResourceSet { entries, locations }
+    }
+
+    /// Returns a copy of this set with the given resources replacing the
+    /// ones with the same IDs, and added if not already present.
+    ///
+    /// Overridden resources have no volume location.
+    pub fn with_overrides(
+        &self,
+        overrides: impl IntoIterator<Item = (ResourceId, Block)>,
+    ) -> ResourceSet {
+        self.with_overlay(&ResourceSet {
+            entries: overrides
+                .into_iter()
+                .map(|(id, block)| (id, LazyBlock::from_factory(move || Ok(block.clone()))))
+                .collect(),
+            locations: BTreeMap::new(),
+        })
+    }
+
+    /// Returns a copy of this set where loaded resources are kept in a
+    /// shared LRU cache, holding at most `capacity_bytes` of resource data.
+    ///
+    /// Repeated loads of a cached resource return the same block without
+    /// reading or decompressing it again.
+    pub fn with_cache(&self, capacity_bytes: usize) -> ResourceSet {
+        let cache = Arc::new(Mutex::new(BlockCache::new(capacity_bytes)));
+        ResourceSet {
+            entries: self
+                .entries
+                .iter()
+                .map(|(id, block)| (*id, cached_lazy_block(cache.clone(), *id, block.clone())))
+                .collect(),
+            locations: self.locations.clone(),
+        }
+    }
+
+    /// Checks that every resource can be read, returning all the problems
+    /// found rather than stopping at the first.
+    ///
+    /// Resources read from volume files must lie within their volume, and
+    /// every resource's contents must load (including decompression).
+    pub fn check_integrity(&self) -> Vec<IntegrityProblem> {
+        let mut volume_sizes: BTreeMap<&Path, Result<u64, String>> = BTreeMap::new();
+        let mut problems = Vec::new();
+        for (id, block) in &self.entries {
+            let mut problem = |reason: String| problems.push(IntegrityProblem { id: *id, reason });
+            if let Some(location) = self.locations.get(id) {
+                let volume_size = volume_sizes.entry(&location.volume).or_insert_with(|| {
+                    std::fs::metadata(&location.volume)
+                        .map(|metadata| metadata.len())
+                        .map_err(|err| err.to_string())
+                });
+                match volume_size {
+                    Err(err) => {
+                        problem(format!("Cannot read {:?}: {}", location.volume, err));
+                        continue;
+                    }
+                    Ok(size) if location.offset >= *size => {
+                        problem(format!(
+                            "Offset 0x{:X} is past the end of {:?} ({} bytes)",
+                            location.offset, location.volume, size
+                        ));
+                        continue;
+                    }
+                    Ok(_) => {}
+                }
+            }
+            if let Err(err) = block.open() {
+                problem(format!("Failed to load contents: {}", err));
+            }
         }
-        ResourceSet { entries }
+        problems
     }
 
     pub fn merge(&self, other: &ResourceSet) -> io::Result<ResourceSet> {
         let mut entries = self.entries.clone();
+        let mut locations = self.locations.clone();
         for (id, block) in other.entries.iter() {
             match entries.entry(*id) {
                 btree_map::Entry::Vacant(vac) => {
                     vac.insert(block.clone());
+                    if let Some(location) = other.locations.get(id) {
+                        locations.insert(*id, location.clone());
+                    }
                 }
                 btree_map::Entry::Occupied(_) => {
                     return Err(io::Error::new(
@@ -97,7 +289,7 @@ impl ResourceSet {
                 }
             }
         }
-        Ok(ResourceSet { entries })
+        Ok(ResourceSet { entries, locations })
     }
 }
 
@@ -113,12 +305,39 @@ pub fn open_game_resources(root_dir: &Path) -> anyhow::Result<ResourceSet> {
         let data_file = root_dir.join("RESOURCE.MSG");
         read_resources(&map_file, &data_file)?
     };
-    Ok(main_set.merge(&message_set)?)
+    let mut game_set = main_set.merge(&message_set)?;
+
+    // CD games keep audio in an external volume, indexed by a map resource.
+    // Sound effects use RESOURCE.SFX if present, otherwise they share
+    // RESOURCE.AUD with the speech.
+    let audio_volume = ["RESOURCE.SFX", "RESOURCE.AUD"]
+        .into_iter()
+        .map(|name| root_dir.join(name))
+        .find(|path| path.exists());
+    let audio_map = game_set.get_resource(&ResourceId::new(ResourceType::Map, AUDIO_MAP_NUM));
+    // The rest of the game is usable without audio, so a map or volume that
+    // cannot be read only skips the audio resources.
+    if let (Some(volume_file), Some(audio_map)) = (audio_volume, audio_map) {
+        let audio_set = audio_map
+            .load_data()
+            .and_then(|data| Ok(read_audio_resources(data, &volume_file)?));
+        match audio_set {
+            Ok(audio_set) => game_set = game_set.merge(&audio_set)?,
+            Err(e) => eprintln!(
+                "Warning: skipping audio resources in {:?}: {}",
+                volume_file, e
+            ),
+        }
+    }
+
+    // Loose patch files take precedence over the packed resources.
+    Ok(game_set.with_overlay(&read_patches(root_dir)?))
 }
 
 pub struct Resource {
     id: ResourceId,
     source: LazyBlock,
+    location: Option<VolumeLocation>,
 }
 
 impl Resource {
@@ -129,4 +348,420 @@ impl Resource {
     pub fn load_data(&self) -> anyhow::Result<Block> {
         Ok(self.source.open()?)
     }
+
+    /// Returns where the resource is stored on disk, if it was read from a
+    /// volume file.
+    pub fn location(&self) -> Option<&VolumeLocation> {
+        self.location.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an SCI audio resource with the given sample data.
+    fn sol_audio_resource(samples: &[u8]) -> Vec<u8> {
+        let mut data = vec![u8::from(ResourceType::Audio), 11];
+        data.extend_from_slice(b"SOL\0");
+        data.extend_from_slice(&22050u16.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        data.extend_from_slice(samples);
+        data
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sci-resources-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn cached_resources_load_once_until_evicted() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let loads = Arc::new(AtomicUsize::new(0));
+        let entries = (1..=3)
+            .map(|num| {
+                let loads = loads.clone();
+                (
+                    ResourceId::new(ResourceType::Text, num),
+                    LazyBlock::from_factory(move || {
+                        loads.fetch_add(1, Ordering::SeqCst);
+                        Ok(Block::from_vec(vec![num as u8; 10]))
+                    }),
+                )
+            })
+            .collect();
+        let resource_set = ResourceSet {
+            entries,
+            locations: BTreeMap::new(),
+        }
+        .with_cache(25);
+        let load = |num| {
+            resource_set
+                .get_resource(&ResourceId::new(ResourceType::Text, num))
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec()
+        };
+
+        assert_eq!(load(1), vec![1; 10]);
+        assert_eq!(load(1), vec![1; 10]);
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // Resources 1 and 2 fit in the cache. Using 1 again makes 2 the least
+        // recently used, so loading 3 evicts 2.
+        load(2);
+        load(1);
+        load(3);
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+        load(1);
+        load(3);
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+        load(2);
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn resource_location_matches_map_entry() {
+        // Two uncompressed Text resources, the second starting at an even
+        // offset as required by the map format.
+        let mut data = vec![0xEE; 4];
+        let mut offsets = Vec::new();
+        for (num, contents) in [(1u16, &b"abc"[..]), (2, &b"hello"[..])] {
+            if !data.len().is_multiple_of(2) {
+                data.push(0);
+            }
+            offsets.push((num, data.len() as u32, contents.len() as u16));
+            data.push(u8::from(ResourceType::Text));
+            data.extend_from_slice(&num.to_le_bytes());
+            data.extend_from_slice(&(contents.len() as u16).to_le_bytes());
+            data.extend_from_slice(&(contents.len() as u16).to_le_bytes());
+            data.extend_from_slice(&0u16.to_le_bytes());
+            data.extend_from_slice(contents);
+        }
+
+        let mut map = vec![u8::from(ResourceType::Text)];
+        map.extend_from_slice(&6u16.to_le_bytes());
+        map.push(0xFF);
+        map.extend_from_slice(&(6 + 5 * offsets.len() as u16).to_le_bytes());
+        for &(num, offset, _) in &offsets {
+            map.extend_from_slice(&num.to_le_bytes());
+            map.extend_from_slice(&(offset >> 1).to_le_bytes()[..3]);
+        }
+
+        let map_path = temp_path("location.map");
+        let data_path = temp_path("location.000");
+        std::fs::write(&map_path, &map).unwrap();
+        std::fs::write(&data_path, &data).unwrap();
+        let resource_set = read_resources(&map_path, &data_path);
+        std::fs::remove_file(&map_path).unwrap();
+        let resource_set = resource_set.unwrap();
+
+        for (num, offset, size) in offsets {
+            let resource = resource_set
+                .get_resource(&ResourceId::new(ResourceType::Text, num))
+                .unwrap();
+            assert_eq!(
+                resource.location(),
+                Some(&VolumeLocation {
+                    volume: data_path.clone(),
+                    offset: offset as u64,
+                    packing: Some(Packing {
+                        compression_type: 0,
+                        packed_size: size,
+                        unpacked_size: size,
+                    }),
+                })
+            );
+        }
+        std::fs::remove_file(&data_path).unwrap();
+
+        let in_memory = ResourceSet {
+            entries: [(
+                ResourceId::new(ResourceType::Text, 3),
+                LazyBlock::from_factory(|| Ok(Block::from_vec(vec![]))),
+            )]
+            .into_iter()
+            .collect(),
+            locations: BTreeMap::new(),
+        };
+        let merged = resource_set.merge(&in_memory).unwrap();
+        assert!(merged
+            .get_resource(&ResourceId::new(ResourceType::Text, 1))
+            .unwrap()
+            .location()
+            .is_some());
+        assert!(merged
+            .get_resource(&ResourceId::new(ResourceType::Text, 3))
+            .unwrap()
+            .location()
+            .is_none());
+    }
+
+    #[test]
+    fn check_integrity_reports_out_of_bounds_locations() {
+        let volume_path = temp_path("integrity.000");
+        std::fs::write(&volume_path, [0u8; 16]).unwrap();
+
+        let text = |num| ResourceId::new(ResourceType::Text, num);
+        let location = |offset| VolumeLocation {
+            volume: volume_path.clone(),
+            offset,
+            packing: None,
+        };
+        let resource_set = ResourceSet {
+            entries: [
+                (
+                    text(1),
+                    LazyBlock::from_factory(|| Ok(Block::from_vec(vec![1, 2, 3]))),
+                ),
+                (
+                    text(2),
+                    LazyBlock::from_factory(|| Ok(Block::from_vec(vec![]))),
+                ),
+                (
+                    text(3),
+                    LazyBlock::from_factory(|| {
+                        Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated").into())
+                    }),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            locations: [(text(1), location(4)), (text(2), location(100))]
+                .into_iter()
+                .collect(),
+        };
+        let problems = resource_set.check_integrity();
+        std::fs::remove_file(&volume_path).unwrap();
+
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.id)
+                .collect::<Vec<_>>(),
+            vec![text(2), text(3)]
+        );
+        assert!(
+            problems[0].reason.contains("past the end"),
+            "{}",
+            problems[0].reason
+        );
+        assert!(
+            problems[1].reason.contains("truncated"),
+            "{}",
+            problems[1].reason
+        );
+    }
+
+    #[test]
+    fn check_integrity_reports_bad_map_entries() {
+        // One good Text resource at offset 0, 10 bytes long.
+        let mut data = vec![u8::from(ResourceType::Text)];
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.push(b'a');
+        // Resource 2 starts 6 bytes before the end, too short for a header,
+        // and resource 3 starts past the end.
+        data.extend_from_slice(&[0; 6]);
+        let entries = [(1u16, 0u32), (2, 10), (3, 0x100)];
+
+        let mut map = vec![u8::from(ResourceType::Text)];
+        map.extend_from_slice(&6u16.to_le_bytes());
+        map.push(0xFF);
+        map.extend_from_slice(&(6 + 5 * entries.len() as u16).to_le_bytes());
+        for (num, offset) in entries {
+            map.extend_from_slice(&num.to_le_bytes());
+            map.extend_from_slice(&(offset >> 1).to_le_bytes()[..3]);
+        }
+
+        let map_path = temp_path("integrity.map");
+        let data_path = temp_path("integrity-map.000");
+        std::fs::write(&map_path, &map).unwrap();
+        std::fs::write(&data_path, &data).unwrap();
+        let resource_set = read_resources(&map_path, &data_path).unwrap();
+        let problems = resource_set.check_integrity();
+        std::fs::remove_file(&map_path).unwrap();
+        std::fs::remove_file(&data_path).unwrap();
+
+        let text = |num| ResourceId::new(ResourceType::Text, num);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.id)
+                .collect::<Vec<_>>(),
+            vec![text(2), text(3)]
+        );
+        assert!(
+            problems[0].reason.contains("Failed to load"),
+            "{}",
+            problems[0].reason
+        );
+        assert!(
+            problems[1].reason.contains("past the end"),
+            "{}",
+            problems[1].reason
+        );
+        assert_eq!(
+            resource_set
+                .get_resource(&text(1))
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec(),
+            b"a"
+        );
+    }
+
+    #[test]
+    fn audio_resources_load_from_volume() {
+        let first = sol_audio_resource(&[1, 2, 3, 4]);
+        let mut second = b"RIFF".to_vec();
+        second.extend_from_slice(&4u32.to_le_bytes());
+        second.extend_from_slice(b"WAVE");
+
+        let mut volume = vec![0xEE; 3];
+        let first_offset = volume.len() as u32;
+        volume.extend_from_slice(&first);
+        let second_offset = volume.len() as u32;
+        volume.extend_from_slice(&second);
+        // Trailing data that should not be included in the last resource.
+        volume.extend_from_slice(&[0xEE; 5]);
+
+        let mut map = Vec::new();
+        for (num, offset) in [(10u16, first_offset), (20, second_offset)] {
+            map.extend_from_slice(&num.to_le_bytes());
+            map.extend_from_slice(&offset.to_le_bytes());
+        }
+        map.extend_from_slice(&0xFFFFu16.to_le_bytes());
+
+        let volume_path = temp_path("audio-test.aud");
+        std::fs::write(&volume_path, &volume).unwrap();
+        let audio_set = read_audio_resources(Block::from_vec(map), &volume_path);
+        std::fs::remove_file(&volume_path).unwrap();
+        let audio_set = audio_set.unwrap();
+
+        assert_eq!(
+            audio_set.resource_ids().collect::<Vec<_>>(),
+            vec![
+                ResourceId::new(ResourceType::Audio, 10),
+                ResourceId::new(ResourceType::Audio, 20)
+            ]
+        );
+        let load = |num| {
+            audio_set
+                .get_resource(&ResourceId::new(ResourceType::Audio, num))
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(load(10), first);
+        assert_eq!(load(20), second);
+        assert_eq!(
+            audio_set
+                .get_resource(&ResourceId::new(ResourceType::Audio, 20))
+                .unwrap()
+                .location(),
+            Some(&VolumeLocation {
+                volume: volume_path,
+                offset: second_offset as u64,
+                packing: None,
+            })
+        );
+    }
+
+    #[test]
+    fn audio_resources_load_from_late_format_map() {
+        let first = sol_audio_resource(&[1, 2, 3, 4]);
+        let second = sol_audio_resource(&[5, 6]);
+        let mut volume = vec![0xEE; 3];
+        volume.extend_from_slice(&first);
+        volume.extend_from_slice(&second);
+
+        // Offsets are 24-bit deltas from the previous entry, and the
+        // terminating entry is five 0xFF bytes.
+        let mut map = Vec::new();
+        for (num, delta) in [(10u16, 3u32), (20, first.len() as u32)] {
+            map.extend_from_slice(&num.to_le_bytes());
+            map.extend_from_slice(&delta.to_le_bytes()[..3]);
+        }
+        map.extend_from_slice(&[0xFF; 5]);
+        assert_eq!(AudioMapFormat::detect(&map), AudioMapFormat::Late);
+
+        let volume_path = temp_path("late-audio-test.aud");
+        std::fs::write(&volume_path, &volume).unwrap();
+        let audio_set = read_audio_resources(Block::from_vec(map), &volume_path);
+        std::fs::remove_file(&volume_path).unwrap();
+        let audio_set = audio_set.unwrap();
+
+        let load = |num| {
+            audio_set
+                .get_resource(&ResourceId::new(ResourceType::Audio, num))
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(load(10), first);
+        assert_eq!(load(20), second);
+    }
+
+    #[test]
+    fn overrides_replace_and_add_resources() {
+        let text = |num| ResourceId::new(ResourceType::Text, num);
+        let base = ResourceSet {
+            entries: [1, 2]
+                .into_iter()
+                .map(|num| {
+                    (
+                        text(num),
+                        LazyBlock::from_factory(move || Ok(Block::from_vec(vec![num as u8]))),
+                    )
+                })
+                .collect(),
+            locations: [(
+                text(1),
+                VolumeLocation {
+                    volume: PathBuf::from("RESOURCE.000"),
+                    offset: 0,
+                    packing: None,
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let merged = base.with_overrides([
+            (text(1), Block::from_vec(b"new".to_vec())),
+            (text(3), Block::from_vec(b"added".to_vec())),
+        ]);
+
+        let load = |num| {
+            merged
+                .get_resource(&text(num))
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(load(1), b"new");
+        assert_eq!(load(2), [2]);
+        assert_eq!(load(3), b"added");
+        assert_eq!(merged.get_resource(&text(1)).unwrap().location(), None);
+        assert_eq!(merged.resource_ids().count(), 3);
+        // The base set is unchanged.
+        assert_eq!(
+            base.get_resource(&text(1))
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec(),
+            [1]
+        );
+    }
 }
@@ -0,0 +1,164 @@
+use std::io;
+
+use crate::{ResourceId, ResourceType};
+use sci_utils::{
+    block::{BlockReader, BlockSource, LazyBlock},
+    data_reader::DataReader,
+};
+
+/// The resource number of the map that indexes `Audio` resources in an
+/// external audio volume.
+pub const AUDIO_MAP_NUM: u16 = 65535;
+
+/// An entry in the audio map, pointing at a resource in the audio volume.
+#[derive(Debug)]
+pub struct AudioMapEntry {
+    pub resource_num: u16,
+    pub file_offset: u32,
+}
+
+/// The layout of the entries in an audio map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioMapFormat {
+    /// A resource number and an absolute 32-bit offset, 6 bytes per entry.
+    Early,
+    /// A resource number and a 24-bit offset from the previous entry, 5
+    /// bytes per entry.
+    Late,
+}
+
+impl AudioMapFormat {
+    /// Detects the format of the audio map in `data`.
+    ///
+    /// The map ends with an entry filled with 0xFF bytes, so as in ScummVM,
+    /// five trailing 0xFF bytes indicate the late format.
+    pub fn detect(data: &[u8]) -> AudioMapFormat {
+        let trailing = data.iter().rev().take_while(|&&b| b == 0xFF).count();
+        if trailing == 5 {
+            AudioMapFormat::Late
+        } else {
+            AudioMapFormat::Early
+        }
+    }
+}
+
+/// The map of `Audio` resources in an external audio volume.
+///
+/// This is a list of resource numbers and their offsets in the volume,
+/// terminated by a resource number of 0xFFFF. See [`AudioMapFormat`] for the
+/// layouts of the entries.
+#[derive(Debug)]
+pub struct AudioMap {
+    pub entries: Vec<AudioMapEntry>,
+}
+
+impl AudioMap {
+    pub fn read_from<R: DataReader>(mut reader: R, format: AudioMapFormat) -> io::Result<AudioMap> {
+        let mut entries = Vec::new();
+        let mut file_offset = 0u32;
+        loop {
+            let resource_num = reader.read_u16_le()?;
+            if resource_num == 0xFFFF {
+                return Ok(AudioMap { entries });
+            }
+            file_offset =
+                match format {
+                    AudioMapFormat::Early => reader.read_u32_le()?,
+                    AudioMapFormat::Late => file_offset
+                        .checked_add(reader.read_u24_le()?)
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("Audio resource {} offset overflows", resource_num),
+                            )
+                        })?,
+                };
+            entries.push(AudioMapEntry {
+                resource_num,
+                file_offset,
+            });
+        }
+    }
+}
+
+/// Determines the full size of the audio resource at the start of `source`.
+///
+/// Audio resources are either a complete WAV file, or an SCI audio header
+/// (resource type, header size, and a "SOL" header containing the sample
+/// data size) followed by the sample data.
+fn read_audio_resource_size(source: &BlockSource) -> io::Result<u64> {
+    let mut reader = BlockReader::new(source.subblock(..source.size().min(13)).open()?);
+    let mut tag = [0u8; 4];
+    reader.read_exact(&mut tag)?;
+    if &tag == b"RIFF" {
+        let riff_size = reader.read_u32_le()?;
+        return Ok(riff_size as u64 + 8);
+    }
+
+    reader.seek_to(0)?;
+    let res_type = reader.read_u8()?;
+    if res_type != u8::from(ResourceType::Audio) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Expected an audio resource, got type 0x{:02X}", res_type),
+        ));
+    }
+    let header_size = reader.read_u8()?;
+    if header_size < 11 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported audio header size: {}", header_size),
+        ));
+    }
+    // The SOL header is the marker, sample rate, and flags, followed by the
+    // size of the sample data.
+    reader.seek_to(2 + 7)?;
+    let data_size = reader.read_u32_le()?;
+    Ok(2 + header_size as u64 + data_size as u64)
+}
+
+/// An external audio volume (e.g. `RESOURCE.SFX` or `RESOURCE.AUD`).
+pub struct AudioVolume {
+    data: BlockSource,
+}
+
+impl AudioVolume {
+    pub fn new(data: BlockSource) -> AudioVolume {
+        AudioVolume { data }
+    }
+
+    /// Returns the `Audio` resources in the volume referenced by the map.
+    pub fn read_contents(
+        &self,
+        map: &AudioMap,
+    ) -> io::Result<impl Iterator<Item = (ResourceId, LazyBlock)>> {
+        let mut contents = Vec::new();
+        for entry in &map.entries {
+            if entry.file_offset as u64 > self.data.size() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Audio resource {} is at offset {}, past the end of the volume",
+                        entry.resource_num, entry.file_offset
+                    ),
+                ));
+            }
+            let rest = self.data.subblock(entry.file_offset as u64..);
+            let size = read_audio_resource_size(&rest)?;
+            if size > rest.size() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Audio resource {} extends past the end of the volume",
+                        entry.resource_num
+                    ),
+                ));
+            }
+            contents.push((
+                ResourceId::new(ResourceType::Audio, entry.resource_num),
+                rest.subblock(..size).to_lazy_block(),
+            ));
+        }
+        Ok(contents.into_iter())
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use sci_utils::block::{Block, LazyBlock};
+
+use crate::ResourceId;
+
+/// A least-recently-used cache of loaded resource blocks, bounded by the
+/// total size of the cached blocks in bytes.
+pub struct BlockCache {
+    capacity: usize,
+    size: usize,
+    next_tick: u64,
+    entries: HashMap<ResourceId, (Block, u64)>,
+    /// The cached resources, ordered from least to most recently used.
+    lru_order: BTreeMap<u64, ResourceId>,
+}
+
+impl BlockCache {
+    pub fn new(capacity: usize) -> BlockCache {
+        BlockCache {
+            capacity,
+            size: 0,
+            next_tick: 0,
+            entries: HashMap::new(),
+            lru_order: BTreeMap::new(),
+        }
+    }
+
+    fn touch(&mut self, id: ResourceId) -> u64 {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.lru_order.insert(tick, id);
+        tick
+    }
+
+    pub fn get(&mut self, id: &ResourceId) -> Option<Block> {
+        let (_, old_tick) = self.entries.get(id)?;
+        self.lru_order.remove(old_tick);
+        let tick = self.touch(*id);
+        let (block, entry_tick) = self.entries.get_mut(id).unwrap();
+        *entry_tick = tick;
+        Some(block.clone())
+    }
+
+    /// Adds a block to the cache, evicting the least recently used blocks
+    /// until the cache is within its capacity. Blocks larger than the
+    /// capacity are not cached.
+    pub fn insert(&mut self, id: ResourceId, block: Block) {
+        if block.size() > self.capacity {
+            return;
+        }
+        if let Some((old_block, old_tick)) = self.entries.remove(&id) {
+            self.size -= old_block.size();
+            self.lru_order.remove(&old_tick);
+        }
+        while self.size + block.size() > self.capacity {
+            let (_, evicted) = self
+                .lru_order
+                .pop_first()
+                .expect("cache size is non-zero, so it must have entries");
+            let (evicted_block, _) = self.entries.remove(&evicted).unwrap();
+            self.size -= evicted_block.size();
+        }
+        self.size += block.size();
+        let tick = self.touch(id);
+        self.entries.insert(id, (block, tick));
+    }
+}
+
+/// Wraps the lazy block for a resource so that loads go through the cache.
+pub fn cached_lazy_block(
+    cache: Arc<Mutex<BlockCache>>,
+    id: ResourceId,
+    source: LazyBlock,
+) -> LazyBlock {
+    LazyBlock::from_factory(move || {
+        if let Some(block) = cache.lock().unwrap().get(&id) {
+            return Ok(block);
+        }
+        // Load without holding the lock, so other resources can be
+        // loaded in the meantime.
+        let block = source.open()?;
+        cache.lock().unwrap().insert(id, block.clone());
+        Ok(block)
+    })
+}
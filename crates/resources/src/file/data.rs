@@ -3,7 +3,10 @@ use std::io;
 use crate::{ResourceId, ResourceType};
 use sci_utils::{
     block::{BlockSource, LazyBlock},
-    compression::dcl::decompress_dcl,
+    compression::{
+        dcl::decompress_dcl,
+        lzw::{decompress_lzw, decompress_lzw1},
+    },
     data_reader::{DataReader, FromBlockSource},
 };
 
@@ -65,9 +68,23 @@ impl std::fmt::Debug for RawContents {
     }
 }
 
+impl RawContents {
+    /// The size of the resource data as stored in the data file.
+    pub fn packed_size(&self) -> u16 {
+        self.data.size() as u16
+    }
+
+    /// The compression method used to store the resource data.
+    pub fn compression_type(&self) -> u16 {
+        self.compression_type
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Contents {
     id: ResourceId,
+    compression_type: u16,
+    unpacked_size: u16,
     data: LazyBlock,
 }
 
@@ -78,14 +95,33 @@ impl Contents {
     pub fn data(&self) -> &LazyBlock {
         &self.data
     }
+    /// The compression method the resource was stored with on disk. The data
+    /// returned by [`Contents::data`] is always decompressed.
+    pub fn original_compression(&self) -> u16 {
+        self.compression_type
+    }
+    /// The size of the resource once decompressed, as recorded in its header.
+    pub fn unpacked_size(&self) -> u16 {
+        self.unpacked_size
+    }
 }
 
 impl TryFrom<RawContents> for Contents {
     type Error = io::Error;
 
     fn try_from(raw_contents: RawContents) -> Result<Self, Self::Error> {
-        let decompressed_data = match raw_contents.compression_type {
+        let compression_type = raw_contents.compression_type();
+        let unpacked_size = raw_contents.unpacked_size;
+        let decompressed_data = match compression_type {
             0 => raw_contents.data.to_lazy_block(),
+            1 => raw_contents
+                .data
+                .to_lazy_block()
+                .map(move |block| Ok(decompress_lzw(&block)?)),
+            2 => raw_contents
+                .data
+                .to_lazy_block()
+                .map(move |block| Ok(decompress_lzw1(&block)?)),
             18 => raw_contents
                 .data
                 .to_lazy_block()
@@ -113,6 +149,8 @@ impl TryFrom<RawContents> for Contents {
                 ResourceType::try_from(raw_contents.res_type).map_err(io::Error::other)?,
                 raw_contents.res_number,
             ),
+            compression_type,
+            unpacked_size,
             data: decompressed_data,
         })
     }
@@ -128,10 +166,28 @@ impl DataFile {
     }
 
     pub fn read_raw_contents(&self, location: &ResourceLocation) -> io::Result<RawContents> {
-        let (header, rest) =
-            RawEntryHeader::from_block_source(&self.data.subblock(location.file_offset as u64..))?;
+        let offset = location.file_offset as u64;
+        if offset + RawEntryHeader::read_size() as u64 > self.data.size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Resource header at offset 0x{:X} extends past the end of the volume ({} bytes)",
+                    offset,
+                    self.data.size()
+                ),
+            ));
+        }
+        let (header, rest) = RawEntryHeader::from_block_source(&self.data.subblock(offset..))?;
+        if header.packed_size as u64 > rest.size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Resource data at offset 0x{:X} ({} bytes) extends past the end of the volume",
+                    offset, header.packed_size
+                ),
+            ));
+        }
         let resource_block = rest.subblock(..header.packed_size as u64);
-        assert_eq!(resource_block.size(), header.packed_size as u64);
         Ok(RawContents {
             res_type: header.res_type,
             res_number: header.res_number,
@@ -140,9 +196,4 @@ impl DataFile {
             data: resource_block,
         })
     }
-
-    pub fn read_contents(&self, location: &ResourceLocation) -> io::Result<Contents> {
-        let raw_contents = self.read_raw_contents(location)?;
-        raw_contents.try_into()
-    }
 }
@@ -0,0 +1,216 @@
+//! Loose patch files, which override individual resources in the game's
+//! volumes.
+//!
+//! A patch file is named `<number>.<ext>`, where the extension is given by
+//! [`ResourceType::to_file_ext`]. Its contents are the resource type byte,
+//! a byte giving the size of an extra header to skip, and then the resource
+//! data.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use sci_utils::block::{Block, BlockSource};
+
+use crate::{ResourceId, ResourceType};
+
+use super::ResourceSet;
+
+/// Returns the name of the patch file for the given resource.
+pub fn patch_file_name(id: ResourceId) -> String {
+    format!(
+        "{}.{}",
+        id.resource_num(),
+        id.type_id().to_file_ext().to_uppercase()
+    )
+}
+
+/// Parses a patch file name, returning None if it does not name a resource.
+fn parse_patch_file_name(name: &str) -> Option<ResourceId> {
+    let (num, ext) = name.split_once('.')?;
+    Some(ResourceId::new(
+        ResourceType::from_file_ext(ext)?,
+        num.parse().ok()?,
+    ))
+}
+
+/// Writes `data` as a patch file for resource `id` in `dir`, replacing any
+/// existing patch for the resource. Returns the path of the written file.
+///
+/// The patch is written to a temporary file first, and then moved into
+/// place, so a failed write never leaves a truncated patch behind.
+pub fn write_patch(dir: &Path, id: ResourceId, data: &Block) -> io::Result<PathBuf> {
+    let path = dir.join(patch_file_name(id));
+    let tmp_path = write_temp_patch(dir, id, data)?;
+    std::fs::rename(&tmp_path, &path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })?;
+    Ok(path)
+}
+
+/// Writes `data` as a patch file for resource `id` in `dir`, failing with
+/// [`io::ErrorKind::AlreadyExists`] if a patch for the resource already
+/// exists. Returns the path of the written file.
+///
+/// Like [`write_patch`], the patch only appears once it is fully written.
+pub fn write_new_patch(dir: &Path, id: ResourceId, data: &Block) -> io::Result<PathBuf> {
+    let path = dir.join(patch_file_name(id));
+    let tmp_path = write_temp_patch(dir, id, data)?;
+    // Unlike a rename, linking fails if the patch already exists.
+    let result = std::fs::hard_link(&tmp_path, &path);
+    let _ = std::fs::remove_file(&tmp_path);
+    result?;
+    Ok(path)
+}
+
+/// Writes the patch file for resource `id` to a temporary file in `dir`,
+/// which the patch reader ignores. Returns the path of the temporary file.
+/// The file is removed if writing fails.
+fn write_temp_patch(dir: &Path, id: ResourceId, data: &Block) -> io::Result<PathBuf> {
+    let tmp_path = dir.join(format!(
+        "{}.{}.tmp",
+        patch_file_name(id),
+        std::process::id()
+    ));
+    let result = File::create(&tmp_path).and_then(|file| {
+        let mut file = io::BufWriter::new(file);
+        file.write_all(&[id.type_id().into(), 0])?;
+        data.write_to(&mut file)?;
+        file.flush()
+    });
+    if let Err(err) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+    Ok(tmp_path)
+}
+
+/// Reads all of the patch files in `dir`.
+///
+/// Files that are not named like patch files, or whose header does not
+/// match the resource type in their name, are ignored, as the interpreter
+/// does.
+pub fn read_patches(dir: &Path) -> io::Result<ResourceSet> {
+    let mut entries = BTreeMap::new();
+    for dir_entry in std::fs::read_dir(dir)? {
+        let dir_entry = dir_entry?;
+        let Some(id) = dir_entry
+            .file_name()
+            .to_str()
+            .and_then(parse_patch_file_name)
+        else {
+            continue;
+        };
+        if !dir_entry.file_type()?.is_file() {
+            continue;
+        }
+        let source = BlockSource::from_path(&dir_entry.path())?;
+        if source.size() < 2 {
+            continue;
+        }
+        let header = source.subblock(..2).open()?;
+        let data_start = 2 + header[1] as u64;
+        if header[0] != u8::from(id.type_id()) || data_start > source.size() {
+            continue;
+        }
+        entries.insert(id, source.subblock(data_start..).to_lazy_block());
+    }
+    Ok(ResourceSet {
+        entries,
+        locations: BTreeMap::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sci_utils::block::LazyBlock;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("sci-resources-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn patch_file_names_round_trip() {
+        let id = ResourceId::new(ResourceType::Script, 100);
+        assert_eq!(patch_file_name(id), "100.SCR");
+        assert_eq!(parse_patch_file_name("100.SCR"), Some(id));
+        assert_eq!(parse_patch_file_name("100.scr"), Some(id));
+        assert_eq!(parse_patch_file_name("RESOURCE.MAP"), None);
+        assert_eq!(parse_patch_file_name("100.TXT"), None);
+    }
+
+    #[test]
+    fn written_patches_override_packed_resources() {
+        let dir = temp_dir("patches");
+        let patched = ResourceId::new(ResourceType::Text, 1);
+        let untouched = ResourceId::new(ResourceType::Text, 2);
+        let packed = ResourceSet {
+            entries: [
+                (
+                    patched,
+                    LazyBlock::from_factory(|| Ok(Block::from_vec(b"old".to_vec()))),
+                ),
+                (
+                    untouched,
+                    LazyBlock::from_factory(|| Ok(Block::from_vec(b"same".to_vec()))),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            locations: BTreeMap::new(),
+        };
+
+        let path = write_patch(&dir, patched, &Block::from_vec(b"new".to_vec())).unwrap();
+        // A file with a mismatched header is not treated as a patch.
+        std::fs::write(dir.join("2.TEX"), [u8::from(ResourceType::Script), 0]).unwrap();
+        let patches = read_patches(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(path.file_name().unwrap(), "1.TEX");
+        let game = packed.with_overlay(&patches);
+        let load = |id| {
+            game.get_resource(&id)
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(load(patched), b"new");
+        assert_eq!(load(untouched), b"same");
+    }
+
+    #[test]
+    fn new_patches_do_not_replace_existing_ones() {
+        let dir = temp_dir("new-patches");
+        let id = ResourceId::new(ResourceType::Text, 1);
+        write_new_patch(&dir, id, &Block::from_vec(b"first".to_vec())).unwrap();
+        let err = write_new_patch(&dir, id, &Block::from_vec(b"second".to_vec())).unwrap_err();
+        let patches = read_patches(&dir).unwrap();
+        let files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        // No temporary files are left behind.
+        assert_eq!(files, vec!["1.TEX"]);
+        assert_eq!(
+            patches
+                .get_resource(&id)
+                .unwrap()
+                .load_data()
+                .unwrap()
+                .to_vec(),
+            b"first"
+        );
+    }
+}
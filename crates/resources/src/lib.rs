@@ -32,6 +32,148 @@ pub enum ResourceType {
     Rave,
 }
 
+impl ResourceType {
+    /// Returns the name SCI tools and documentation use for this resource
+    /// type (e.g. `"vocab"`, or `"xlate"` for translations). This differs
+    /// from the `Debug` output, which uses the variant name.
+    pub fn canonical_name(&self) -> &'static str {
+        match self {
+            ResourceType::View => "view",
+            ResourceType::Pic => "pic",
+            ResourceType::Script => "script",
+            ResourceType::Text => "text",
+            ResourceType::Sound => "sound",
+            ResourceType::Memory => "memory",
+            ResourceType::Vocab => "vocab",
+            ResourceType::Font => "font",
+            ResourceType::Cursor => "cursor",
+            ResourceType::Patch => "patch",
+            ResourceType::Bitmap => "bitmap",
+            ResourceType::Palette => "palette",
+            ResourceType::CdAudio => "cdaudio",
+            ResourceType::Audio => "audio",
+            ResourceType::Sync => "sync",
+            ResourceType::Message => "message",
+            ResourceType::Map => "map",
+            ResourceType::Heap => "heap",
+            ResourceType::Audio36 => "audio36",
+            ResourceType::Sync36 => "sync36",
+            ResourceType::Translation => "xlate",
+            ResourceType::Rave => "rave",
+        }
+    }
+    /// Returns the file extension used for patch files of this resource
+    /// type (without the dot). Each type has a distinct extension, so
+    /// [`ResourceType::from_file_ext`] recovers the type exactly.
+    ///
+    /// These are the extensions SCI interpreters look for where one exists.
+    /// Types with no established extension use the first three letters of
+    /// their name, and the 36-style audio types use `a36` and `s36` to keep
+    /// them apart from each other.
+    pub fn to_file_ext(&self) -> &'static str {
+        match self {
+            ResourceType::View => "v56",
+            ResourceType::Pic => "p56",
+            ResourceType::Script => "scr",
+            ResourceType::Text => "tex",
+            ResourceType::Sound => "snd",
+            ResourceType::Memory => "mem",
+            ResourceType::Vocab => "voc",
+            ResourceType::Font => "fon",
+            ResourceType::Cursor => "cur",
+            ResourceType::Patch => "pat",
+            ResourceType::Bitmap => "bmp",
+            ResourceType::Palette => "pal",
+            ResourceType::CdAudio => "cda",
+            ResourceType::Audio => "aud",
+            ResourceType::Sync => "syn",
+            ResourceType::Message => "msg",
+            ResourceType::Map => "map",
+            ResourceType::Heap => "hep",
+            ResourceType::Audio36 => "a36",
+            ResourceType::Sync36 => "s36",
+            ResourceType::Translation => "trn",
+            ResourceType::Rave => "rav",
+        }
+    }
+
+    /// Returns the resource type with the given patch file extension (case
+    /// insensitive), or None if no type uses it.
+    pub fn from_file_ext(ext: &str) -> Option<ResourceType> {
+        match ext.to_ascii_lowercase().as_str() {
+            "v56" => Some(ResourceType::View),
+            "p56" => Some(ResourceType::Pic),
+            "scr" => Some(ResourceType::Script),
+            "tex" => Some(ResourceType::Text),
+            "snd" => Some(ResourceType::Sound),
+            "mem" => Some(ResourceType::Memory),
+            "voc" => Some(ResourceType::Vocab),
+            "fon" => Some(ResourceType::Font),
+            "cur" => Some(ResourceType::Cursor),
+            "pat" => Some(ResourceType::Patch),
+            "bmp" => Some(ResourceType::Bitmap),
+            "pal" => Some(ResourceType::Palette),
+            "cda" => Some(ResourceType::CdAudio),
+            "aud" => Some(ResourceType::Audio),
+            "syn" => Some(ResourceType::Sync),
+            "msg" => Some(ResourceType::Message),
+            "map" => Some(ResourceType::Map),
+            "hep" => Some(ResourceType::Heap),
+            "a36" => Some(ResourceType::Audio36),
+            "s36" => Some(ResourceType::Sync36),
+            "trn" => Some(ResourceType::Translation),
+            "rav" => Some(ResourceType::Rave),
+            _ => None,
+        }
+    }
+
+    /// Returns general information about resources of this type.
+    pub fn describe(&self) -> ResourceTypeInfo {
+        let (compressible, is_text, description) = match self {
+            ResourceType::View => (true, false, "Animated sprite cels and loops"),
+            ResourceType::Pic => (true, false, "Background picture"),
+            ResourceType::Script => (true, false, "Compiled script code"),
+            ResourceType::Text => (true, true, "Table of text strings"),
+            ResourceType::Sound => (true, false, "Music or sound effect"),
+            ResourceType::Memory => (false, false, "Interpreter memory (not stored)"),
+            ResourceType::Vocab => (true, false, "Vocabulary or interpreter table"),
+            ResourceType::Font => (true, false, "Bitmap font"),
+            ResourceType::Cursor => (true, false, "Mouse cursor image"),
+            ResourceType::Patch => (true, false, "Sound driver patch"),
+            ResourceType::Bitmap => (true, false, "Bitmap image"),
+            ResourceType::Palette => (true, false, "Color palette"),
+            ResourceType::CdAudio => (true, false, "CD audio track information"),
+            ResourceType::Audio => (false, false, "Digital audio sample"),
+            ResourceType::Sync => (true, false, "Lip sync data"),
+            ResourceType::Message => (true, true, "Message text for conversations"),
+            ResourceType::Map => (true, false, "Audio resource map"),
+            ResourceType::Heap => (true, false, "Script heap (objects and strings)"),
+            ResourceType::Audio36 => (false, false, "Digital speech for a message"),
+            ResourceType::Sync36 => (false, false, "Lip sync data for a message"),
+            ResourceType::Translation => (true, true, "Message translation table"),
+            ResourceType::Rave => (true, false, "Rave lip sync data"),
+        };
+        ResourceTypeInfo {
+            compressible,
+            is_text,
+            description,
+        }
+    }
+}
+
+/// General information about a resource type, returned by
+/// [`ResourceType::describe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceTypeInfo {
+    /// Whether resources of this type may be stored compressed. Audio kept
+    /// in external audio volumes is always stored as is.
+    pub compressible: bool,
+    /// Whether the resource's content is mostly human readable text.
+    pub is_text: bool,
+    /// A short human readable description of the type.
+    pub description: &'static str,
+}
+
 impl TryFrom<u8> for ResourceType {
     type Error = String;
 
@@ -98,3 +240,82 @@ impl std::fmt::Debug for ResourceId {
         write!(f, "{:?}:{:}", self.type_id, self.resource_num)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_names_match_sci_names() {
+        let expected = [
+            (ResourceType::View, "view"),
+            (ResourceType::Pic, "pic"),
+            (ResourceType::Script, "script"),
+            (ResourceType::Text, "text"),
+            (ResourceType::Sound, "sound"),
+            (ResourceType::Memory, "memory"),
+            (ResourceType::Vocab, "vocab"),
+            (ResourceType::Font, "font"),
+            (ResourceType::Cursor, "cursor"),
+            (ResourceType::Patch, "patch"),
+            (ResourceType::Bitmap, "bitmap"),
+            (ResourceType::Palette, "palette"),
+            (ResourceType::CdAudio, "cdaudio"),
+            (ResourceType::Audio, "audio"),
+            (ResourceType::Sync, "sync"),
+            (ResourceType::Message, "message"),
+            (ResourceType::Map, "map"),
+            (ResourceType::Heap, "heap"),
+            (ResourceType::Audio36, "audio36"),
+            (ResourceType::Sync36, "sync36"),
+            (ResourceType::Translation, "xlate"),
+            (ResourceType::Rave, "rave"),
+        ];
+        for (res_type, name) in expected {
+            assert_eq!(res_type.canonical_name(), name, "{:?}", res_type);
+        }
+
+        // Every type byte should be covered by the table above.
+        let all_types = (0x80..=0xFFu8)
+            .filter_map(|byte| ResourceType::try_from(byte).ok())
+            .collect::<Vec<_>>();
+        assert_eq!(all_types.len(), expected.len());
+    }
+
+    #[test]
+    fn file_extensions_round_trip() {
+        let mut exts = std::collections::BTreeSet::new();
+        for value in 0x80..=0x95 {
+            let res_type = ResourceType::try_from(value).unwrap();
+            let ext = res_type.to_file_ext();
+            assert!(exts.insert(ext), "Duplicate extension {:?}", ext);
+            assert_eq!(ResourceType::from_file_ext(ext), Some(res_type));
+            assert_eq!(
+                ResourceType::from_file_ext(&ext.to_uppercase()),
+                Some(res_type)
+            );
+        }
+        assert_eq!(ResourceType::from_file_ext("txt"), None);
+    }
+
+    #[test]
+    fn describe_reports_type_info() {
+        for (res_type, compressible, is_text) in [
+            (ResourceType::Script, true, false),
+            (ResourceType::Text, true, true),
+            (ResourceType::Message, true, true),
+            (ResourceType::View, true, false),
+            (ResourceType::Audio, false, false),
+            (ResourceType::Audio36, false, false),
+        ] {
+            let info = res_type.describe();
+            assert_eq!(
+                (info.compressible, info.is_text),
+                (compressible, is_text),
+                "{:?}",
+                res_type
+            );
+            assert!(!info.description.is_empty());
+        }
+    }
+}
@@ -21,13 +21,11 @@ impl FromFixedBytes for RawClassSpeciesEntry {
 struct ClassSpeciesEntry {
     #[expect(dead_code)]
     species_id: u16,
-    #[expect(dead_code)]
     script_id: u16,
 }
 
 #[derive(Clone, Debug)]
 pub struct ClassSpeciesTable {
-    #[expect(dead_code)]
     species_entries: Vec<ClassSpeciesEntry>,
 }
 
@@ -46,4 +44,33 @@ impl ClassSpeciesTable {
                 .collect(),
         })
     }
+
+    /// Returns the number of the script that defines the class with the
+    /// given species, if the species is in the table.
+    pub fn script_of(&self, species: u16) -> Option<u16> {
+        self.species_entries
+            .get(species as usize)
+            .map(|entry| entry.script_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sci_utils::block::Block;
+
+    use super::*;
+
+    #[test]
+    fn script_of_looks_up_species_by_index() {
+        let data = [(0u16, 10u16), (0, 20), (0, 999)]
+            .into_iter()
+            .flat_map(|(zero, script)| [zero.to_le_bytes(), script.to_le_bytes()])
+            .flatten()
+            .collect::<Vec<_>>();
+        let table = ClassSpeciesTable::load_from(Block::from_vec(data).narrow()).unwrap();
+        assert_eq!(table.script_of(0), Some(10));
+        assert_eq!(table.script_of(1), Some(20));
+        assert_eq!(table.script_of(2), Some(999));
+        assert_eq!(table.script_of(3), None);
+    }
 }
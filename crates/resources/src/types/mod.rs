@@ -3,3 +3,4 @@ pub mod class_species;
 pub mod msg;
 pub mod script;
 pub mod selector_table;
+pub mod vocab;
@@ -1,11 +1,19 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, io};
 
 use sci_utils::{
     block::{Block, BlockReader},
     buffer::Buffer,
     data_reader::DataReader,
+    data_writer::DataWriter,
 };
 
+/// The binary layout versions of message resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormatVersion {
+    /// The SCI1.1 format, with 11 byte records.
+    V4,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MessageId {
     noun: u8,
@@ -15,6 +23,15 @@ pub struct MessageId {
 }
 
 impl MessageId {
+    pub fn new(noun: u8, verb: u8, condition: u8, sequence: u8) -> Self {
+        MessageId {
+            noun,
+            verb,
+            condition,
+            sequence,
+        }
+    }
+
     pub fn noun(&self) -> u8 {
         self.noun
     }
@@ -32,17 +49,29 @@ impl MessageId {
     }
 }
 
+/// The last four bytes of a version 4 message record: the noun, verb, and
+/// condition of a message this one refers to, and a byte of unknown purpose.
+///
+/// All zeros means the message has no reference.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageRef {
+    pub noun: u8,
+    pub verb: u8,
+    pub condition: u8,
+    pub unknown: u8,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct RawMessageRecord {
     id: MessageId,
-    ref_id: MessageId,
+    reference: MessageRef,
     text_offset: u16,
     talker: u8,
 }
 
 #[derive(Debug)]
 pub struct MessageRecord {
-    _ref_id: MessageId,
+    reference: MessageRef,
     text: String,
     talker: u8,
 }
@@ -55,6 +84,10 @@ impl MessageRecord {
     pub fn talker(&self) -> u8 {
         self.talker
     }
+
+    pub fn reference(&self) -> MessageRef {
+        self.reference
+    }
 }
 
 fn parse_message_resource_v4(msg_res: Block) -> anyhow::Result<Vec<RawMessageRecord>> {
@@ -80,25 +113,18 @@ fn parse_message_resource_v4(msg_res: Block) -> anyhow::Result<Vec<RawMessageRec
         let talker = reader.read_u8()?;
         let text_offset = reader.read_u16_le()?;
 
-        let ref_id = {
-            let noun = reader.read_u8()?;
-            let verb = reader.read_u8()?;
-            let condition = reader.read_u8()?;
-            MessageId {
-                noun,
-                verb,
-                condition,
-                sequence: 1,
-            }
-        };
-
         // According to ScummVM, the record size is 11, but I don't know the purpose of
         // the last byte.
-        let _unknown = reader.read_u8()?;
+        let reference = MessageRef {
+            noun: reader.read_u8()?,
+            verb: reader.read_u8()?,
+            condition: reader.read_u8()?,
+            unknown: reader.read_u8()?,
+        };
 
         let raw_record = RawMessageRecord {
             id,
-            ref_id,
+            reference,
             text_offset,
             talker,
         };
@@ -128,7 +154,7 @@ fn resolve_raw_record(
 ) -> anyhow::Result<MessageRecord> {
     let text = read_string_at_offset(msg_res, raw_record.text_offset)?;
     Ok(MessageRecord {
-        _ref_id: raw_record.ref_id,
+        reference: raw_record.reference,
         text,
         talker: raw_record.talker,
     })
@@ -161,3 +187,156 @@ pub fn parse_message_resource(msg_res: Block) -> anyhow::Result<RoomMessageSet>
         .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
     Ok(RoomMessageSet { messages })
 }
+
+/// The size of the header of a version 4 message resource: the version
+/// number, four bytes of unknown purpose, and the message count.
+const V4_HEADER_SIZE: usize = 10;
+/// The size of each record in a version 4 message resource.
+const V4_RECORD_SIZE: usize = 11;
+
+/// A message to write with [`write_message_resource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageEntry<'a> {
+    pub id: MessageId,
+    pub talker: u8,
+    pub text: &'a str,
+    pub reference: MessageRef,
+}
+
+/// Writes a message resource containing the given messages, in the order
+/// given.
+pub fn write_message_resource<'a, W, I>(
+    writer: &mut W,
+    version: MessageFormatVersion,
+    messages: I,
+) -> io::Result<()>
+where
+    W: DataWriter,
+    I: IntoIterator<Item = MessageEntry<'a>>,
+{
+    let MessageFormatVersion::V4 = version;
+    let messages = messages.into_iter().collect::<Vec<_>>();
+    let message_count: u16 = messages.len().try_into().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Too many messages for a resource: {}", messages.len()),
+        )
+    })?;
+
+    writer.write_u32_le(4000)?;
+    writer.write_u32_le(0)?;
+    writer.write_u16_le(message_count)?;
+
+    let mut text_offset = V4_HEADER_SIZE + messages.len() * V4_RECORD_SIZE;
+    for message in &messages {
+        let id = message.id;
+        if message.text.contains('\0') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Message {:?} contains a null character", id),
+            ));
+        }
+        let offset: u16 = text_offset.try_into().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Message {:?} is past the end of the addressable text", id),
+            )
+        })?;
+        writer.write_u8(id.noun)?;
+        writer.write_u8(id.verb)?;
+        writer.write_u8(id.condition)?;
+        writer.write_u8(id.sequence)?;
+        writer.write_u8(message.talker)?;
+        writer.write_u16_le(offset)?;
+        writer.write_u8(message.reference.noun)?;
+        writer.write_u8(message.reference.verb)?;
+        writer.write_u8(message.reference.condition)?;
+        writer.write_u8(message.reference.unknown)?;
+        text_offset += message.text.len() + 1;
+    }
+
+    for message in &messages {
+        for &byte in message.text.as_bytes() {
+            writer.write_u8(byte)?;
+        }
+        writer.write_u8(0)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sci_utils::data_writer::IoDataWriter;
+
+    use super::*;
+
+    fn write_to_block(messages: Vec<MessageEntry>) -> io::Result<Block> {
+        let mut writer = IoDataWriter::new(io::Cursor::new(Vec::new()));
+        write_message_resource(&mut writer, MessageFormatVersion::V4, messages)?;
+        Ok(Block::from_vec(writer.into_inner().into_inner()))
+    }
+
+    fn entry(id: MessageId, talker: u8, text: &str) -> MessageEntry<'_> {
+        MessageEntry {
+            id,
+            talker,
+            text,
+            reference: MessageRef::default(),
+        }
+    }
+
+    fn parsed_entries(parsed: &RoomMessageSet) -> Vec<MessageEntry<'_>> {
+        parsed
+            .messages()
+            .map(|(id, record)| MessageEntry {
+                id: *id,
+                talker: record.talker(),
+                text: record.text(),
+                reference: record.reference(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn written_messages_parse_back() {
+        let messages = vec![
+            entry(MessageId::new(1, 2, 0, 1), 3, "Hello there."),
+            entry(MessageId::new(1, 2, 0, 2), 4, "General Kenobi!"),
+            entry(MessageId::new(5, 1, 7, 1), 0, ""),
+        ];
+        let block = write_to_block(messages.clone()).unwrap();
+        assert_eq!(
+            block.size(),
+            V4_HEADER_SIZE + 3 * V4_RECORD_SIZE + "Hello there.General Kenobi!".len() + 3
+        );
+
+        let parsed = parse_message_resource(block).unwrap();
+        assert_eq!(parsed_entries(&parsed), messages);
+    }
+
+    #[test]
+    fn references_survive_rewriting() {
+        let reference = MessageRef {
+            noun: 4,
+            verb: 2,
+            condition: 1,
+            unknown: 9,
+        };
+        let original = write_to_block(vec![MessageEntry {
+            reference,
+            ..entry(MessageId::new(1, 2, 0, 1), 3, "See the other one.")
+        }])
+        .unwrap();
+
+        let parsed = parse_message_resource(original.clone()).unwrap();
+        let rewritten = write_to_block(parsed_entries(&parsed)).unwrap();
+        assert_eq!(rewritten.to_vec(), original.to_vec());
+        let reparsed = parse_message_resource(rewritten).unwrap();
+        assert_eq!(reparsed.messages().next().unwrap().1.reference(), reference);
+    }
+
+    #[test]
+    fn write_rejects_null_in_text() {
+        assert!(write_to_block(vec![entry(MessageId::new(1, 1, 0, 1), 0, "a\0b")]).is_err());
+    }
+}
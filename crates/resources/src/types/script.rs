@@ -2,7 +2,7 @@ use sci_utils::{
     block::{Block, BlockReader},
     buffer::{Buffer, BufferOpsExt, FromFixedBytes},
     data_reader::DataReader,
-    numbers::{modify_u16_le_in_slice, read_u16_le_from_slice},
+    numbers::{is_aligned, modify_u16_le_in_slice, read_u16_le_from_slice},
 };
 
 use super::selector_table::SelectorTable;
@@ -20,32 +20,18 @@ where
     Ok(())
 }
 
-#[expect(dead_code)]
-fn read_null_terminated_string_at(buffer: &[u8], offset: usize) -> anyhow::Result<&str> {
-    let null_pos = buffer[offset..]
-        .iter()
-        .position(|&b| b == 0)
-        .ok_or_else(|| anyhow::anyhow!("No null terminator found in string"))?;
-    Ok(std::str::from_utf8(&buffer[offset..offset + null_pos])?)
-}
-
 pub struct Heap {
     #[expect(dead_code)]
     resource_data: Block,
     #[expect(dead_code)]
     locals: Block,
-    #[expect(dead_code)]
     objects: Vec<Object>,
     #[expect(dead_code)]
     strings: Vec<Block>,
 }
 
 impl Heap {
-    pub fn from_block(
-        selector_table: &SelectorTable,
-        loaded_script: &Block,
-        resource_data: Block,
-    ) -> anyhow::Result<Heap> {
+    pub fn from_block(loaded_script: &Block, resource_data: Block) -> anyhow::Result<Heap> {
         let relocations_offset = resource_data.read_u16_le_at(0);
         let heap_data = resource_data
             .clone()
@@ -64,22 +50,14 @@ impl Heap {
                 break;
             }
 
-            println!("Object: {:04X}", resource_data.offset_in(&heap_data));
-
             anyhow::ensure!(magic == 0x1234u16);
             let object_size = heap_data.read_u16_le_at(2);
             let (object_data, next_heap_data) = heap_data.split_at((object_size * 2) as usize);
-            let new_obj = Object::from_block(selector_table, loaded_script, object_data)?;
-            println!("Object: {:?}", new_obj);
+            let new_obj = Object::from_block(loaded_script, object_data)?;
             objects.push(new_obj);
             heap_data = next_heap_data;
         }
 
-        println!(
-            "Strings offset: {:04X}",
-            resource_data.offset_in(&heap_data)
-        );
-
         let mut strings = Vec::new();
         // Find all strings
         while !heap_data.is_empty() {
@@ -87,11 +65,6 @@ impl Heap {
                 anyhow::bail!("No null terminator found in string heap");
             };
             let (string_data, next_heap_data) = heap_data.split_at(null_pos + 1);
-            println!(
-                "String @{:04X}: {:?}",
-                resource_data.offset_in(&string_data),
-                std::str::from_utf8(&string_data[..string_data.len() - 1]).unwrap()
-            );
             strings.push(string_data);
             heap_data = next_heap_data;
         }
@@ -139,21 +112,49 @@ impl PropertySelectors {
     }
 }
 
+/// The value of an object property, classified by
+/// [`Object::typed_value`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyValue {
+    /// A signed number, such as a coordinate.
+    Int(i16),
+    /// An unsigned number, or a value with no known type.
+    UInt(u16),
+    /// The address of an object, or 0 for none.
+    ObjectRef(u16),
+}
+
+/// Properties whose values are signed, by selector name.
+const SIGNED_PROPERTIES: &[&str] = &[
+    "x", "y", "z", "xStep", "yStep", "xLast", "yLast", "priority", "heading", "nsLeft", "nsTop",
+    "nsRight", "nsBottom", "brLeft", "brTop", "brRight", "brBottom",
+];
+
+/// Properties whose values are object addresses, by selector name.
+const OBJECT_PROPERTIES: &[&str] = &[
+    "client", "owner", "mover", "cycler", "looper", "avoider", "caller", "script", "timer",
+    "scaler", "elements", "code",
+];
+
+fn classify_property(name: Option<&str>, value: u16) -> PropertyValue {
+    match name {
+        Some(name) if SIGNED_PROPERTIES.contains(&name) => PropertyValue::Int(value as i16),
+        Some(name) if OBJECT_PROPERTIES.contains(&name) => PropertyValue::ObjectRef(value),
+        _ => PropertyValue::UInt(value),
+    }
+}
+
 pub struct Object {
     #[expect(dead_code)]
     obj_data: Block,
     var_selector_ids: PropertySelectors,
-    #[expect(dead_code)]
     method_records: Vec<MethodRecord>,
     properties: Vec<u16>,
+    name: Option<String>,
 }
 
 impl Object {
-    pub fn from_block(
-        selector_table: &SelectorTable,
-        loaded_data: &Block,
-        obj_data: Block,
-    ) -> anyhow::Result<Object> {
+    pub fn from_block(loaded_data: &Block, obj_data: Block) -> anyhow::Result<Object> {
         let var_selector_offfset = obj_data.read_u16_le_at(4);
         let method_record_offset = obj_data.read_u16_le_at(6);
         let padding = obj_data.read_u16_le_at(8);
@@ -164,11 +165,6 @@ impl Object {
             .sub_buffer(var_selector_offfset as usize..method_record_offset as usize)
             .split_values::<u16>()?;
 
-        for var_selector_id in &var_selector_ids {
-            let selector = selector_table.get_selector_by_id(*var_selector_id);
-            println!("Var selector: {:04X}: {:?}", *var_selector_id, selector);
-        }
-
         let var_selector_ids = PropertySelectors::new(var_selector_ids);
 
         let (method_records, _) = loaded_data
@@ -176,18 +172,14 @@ impl Object {
             .sub_buffer(method_record_offset as usize..)
             .read_length_delimited_records::<MethodRecord>()?;
 
-        for method_record in &method_records {
-            let selector = selector_table.get_selector_by_id(method_record.selector_id);
-            println!(
-                "Method: {:04X}: {:?}, offset: {:04X}",
-                method_record.selector_id, selector, method_record.method_offset
-            );
-        }
-
         let properties = obj_data.clone().split_values::<u16>()?;
 
-        println!("Num variable selectors: {:?}", var_selector_ids.len());
-        println!("Num object properties: {:?}", properties.len());
+        // The name property holds the (relocated) offset of the object's name
+        // in the loaded script.
+        let name = properties
+            .get(8)
+            .map(|&offset| offset as usize)
+            .and_then(|offset| loaded_data.read_cstr_at(offset).ok());
 
         let is_class = properties[7] & 0x8000 != 0;
 
@@ -211,12 +203,97 @@ impl Object {
             var_selector_ids,
             method_records,
             properties,
+            name,
         })
     }
 
     pub fn is_class(&self) -> bool {
         self.properties[7] & 0x8000 != 0
     }
+
+    /// The species (class number) of this object.
+    pub fn species(&self) -> u16 {
+        self.properties[5]
+    }
+
+    /// The species of this object's superclass.
+    pub fn super_class(&self) -> u16 {
+        self.properties[6]
+    }
+
+    /// The name of the object, if it could be resolved from the loaded script.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn num_properties(&self) -> usize {
+        self.properties.len()
+    }
+
+    /// Returns the selector IDs of the object's properties, in property
+    /// order.
+    ///
+    /// Only classes carry a property dictionary, so this is empty for
+    /// instances.
+    pub fn property_selectors(&self) -> &[u16] {
+        &self.var_selector_ids.var_selector_ids
+    }
+
+    /// Returns the raw value of the property at `index`.
+    pub fn base_value(&self, index: usize) -> Option<u16> {
+        self.properties.get(index).copied()
+    }
+
+    /// Returns the value of the property at `index`, classified by the name
+    /// of its selector.
+    ///
+    /// There is no type information in the script, so this uses a fixed list
+    /// of well-known property names. Properties with other names, and all
+    /// properties of instances, which have no property dictionary, are
+    /// treated as unsigned.
+    pub fn typed_value(&self, index: usize, selectors: &SelectorTable) -> Option<PropertyValue> {
+        let value = self.base_value(index)?;
+        let name = self
+            .property_selectors()
+            .get(index)
+            .and_then(|&id| selectors.get_selector_by_id(id))
+            .map(|selector| selector.name());
+        Some(classify_property(name, value))
+    }
+
+    pub fn num_methods(&self) -> usize {
+        self.method_records.len()
+    }
+
+    /// Returns the (selector ID, code offset) pair of each method this object
+    /// defines, in method table order. Code offsets are relative to the start
+    /// of the loaded script.
+    ///
+    /// Methods inherited without being overridden are not in an object's
+    /// method table, and are not included.
+    pub fn methods(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.method_records
+            .iter()
+            .map(|record| (record.selector_id, record.method_offset))
+    }
+
+    /// Returns the offset of the method implementing the given selector, if
+    /// this object defines one.
+    ///
+    /// Returns None if the method is inherited without being overridden.
+    pub fn method_offset(&self, selector_id: u16) -> Option<u16> {
+        self.method_records
+            .iter()
+            .find(|record| record.selector_id == selector_id)
+            .map(|record| record.method_offset)
+    }
+
+    /// Returns true if the selector is one of this object's properties in
+    /// its property dictionary, or a method it defines.
+    pub fn uses_selector(&self, selector_id: u16) -> bool {
+        self.property_selectors().contains(&selector_id)
+            || self.method_offset(selector_id).is_some()
+    }
 }
 
 impl std::fmt::Debug for Object {
@@ -283,24 +360,129 @@ where
 pub struct LoadedScript {
     #[expect(dead_code)]
     heap_offset: u16,
-    #[expect(dead_code)]
     full_buffer: Block,
     #[expect(dead_code)]
     script: Script,
-    #[expect(dead_code)]
     heap: Heap,
 }
 
-pub fn load_script<B>(
-    selector_table: &SelectorTable,
-    script_data: &B,
-    heap_data: &B,
-) -> anyhow::Result<LoadedScript>
+impl LoadedScript {
+    /// Returns the objects and classes defined in the script's heap.
+    pub fn objects(&self) -> &[Object] {
+        &self.heap.objects
+    }
+
+    /// Returns the relocated script and heap data, concatenated. Method
+    /// offsets are relative to the start of this buffer.
+    pub fn data(&self) -> &Block {
+        &self.full_buffer
+    }
+
+    /// Returns a human readable report of the objects and classes in the
+    /// script, with their property and method tables.
+    ///
+    /// Property names are taken from the object's own property dictionary,
+    /// or for instances, from their class if it is defined in this script.
+    pub fn debug_dump(&self, selectors: &SelectorTable) -> String {
+        use std::fmt::Write;
+
+        let selector_name = |id: u16| {
+            selectors
+                .get_selector_by_id(id)
+                .map_or_else(|| format!("#{:04X}", id), |sel| sel.name().to_string())
+        };
+        let mut out = String::new();
+        for object in self.objects() {
+            let prop_selectors = if object.is_class() {
+                Some(&object.var_selector_ids)
+            } else {
+                self.objects()
+                    .iter()
+                    .find(|class| class.is_class() && class.species() == object.species())
+                    .map(|class| &class.var_selector_ids)
+            };
+
+            writeln!(
+                out,
+                "{} {}: species {:04X}, super {:04X}",
+                if object.is_class() { "Class" } else { "Object" },
+                object.name().unwrap_or("*NO NAME*"),
+                object.species(),
+                object.super_class(),
+            )
+            .unwrap();
+            writeln!(out, "  Properties:").unwrap();
+            for (index, value) in object.properties.iter().enumerate() {
+                let name = prop_selectors
+                    .and_then(|sels| sels.var_selector_ids.get(index))
+                    .map_or_else(|| format!("#{}", index), |&id| selector_name(id));
+                writeln!(out, "    +{:04X} {} = {:04X}", index * 2, name, value).unwrap();
+            }
+            writeln!(out, "  Methods:").unwrap();
+            for (selector_id, code_offset) in object.methods() {
+                writeln!(
+                    out,
+                    "    {} @ {:04X}",
+                    selector_name(selector_id),
+                    code_offset
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Returns every class in `scripts` whose properties or methods use the
+/// selector named `name`, with the number of the script defining it.
+///
+/// `scripts` pairs each loaded script with its script number. Instances are
+/// not included, and neither are classes that only inherit the selector.
+pub fn selector_references<'a, I>(
+    scripts: I,
+    selectors: &SelectorTable,
+    name: &str,
+) -> Vec<(u16, &'a Object)>
+where
+    I: IntoIterator<Item = (u16, &'a LoadedScript)>,
+{
+    let Some(selector) = selectors.get_selector_by_name(name) else {
+        return Vec::new();
+    };
+    scripts
+        .into_iter()
+        .flat_map(|(script_num, script)| {
+            script
+                .objects()
+                .iter()
+                .filter(|object| object.is_class() && object.uses_selector(selector.id()))
+                .map(move |object| (script_num, object))
+        })
+        .collect()
+}
+
+/// Returns true if no instance in `scripts` has the given species, meaning
+/// the class is only ever used as a superclass.
+///
+/// This is only meaningful when `scripts` holds every script in the game.
+pub fn is_abstract<'a, I>(scripts: I, species: u16) -> bool
+where
+    I: IntoIterator<Item = &'a LoadedScript>,
+{
+    !scripts.into_iter().any(|script| {
+        script
+            .objects()
+            .iter()
+            .any(|object| !object.is_class() && object.species() == species)
+    })
+}
+
+pub fn load_script<B>(script_data: &B, heap_data: &B) -> anyhow::Result<LoadedScript>
 where
     B: Buffer<'static, Idx = u16> + Clone,
 {
     let heap_offset = script_data.size();
-    anyhow::ensure!(heap_offset % 2 == 0);
+    anyhow::ensure!(is_aligned(heap_offset, 2));
     // Concat the two blocks.
     //
     // It may be possible to get rid of the relocation block, but it's not clear.
@@ -323,7 +505,7 @@ where
     let loaded_script = Block::from_vec(loaded_script);
     let (script_data, heap_data) = loaded_script.clone().split_at(heap_offset);
     let script = Script::from_block(script_data)?;
-    let heap = Heap::from_block(selector_table, &loaded_script, heap_data)?;
+    let heap = Heap::from_block(&loaded_script, heap_data)?;
 
     Ok(LoadedScript {
         heap_offset: heap_offset as u16,
@@ -332,3 +514,157 @@ where
         heap,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// A script defining the class `Thing` and an instance `thing`, both
+    /// implementing `init`.
+    fn fixture_script() -> (Block, Block) {
+        let mut script = words(&[38, 0]);
+        // Class property dictionary, at 4.
+        script.extend(words(&[0, 1, 2, 3, 4, 5, 6, 7, 8]));
+        // Class method dictionary, at 22.
+        script.extend(words(&[1, 9, 34]));
+        // Instance method dictionary, at 28.
+        script.extend(words(&[1, 9, 34]));
+        // Code for both init methods, at 34: ldi 1; ret
+        script.extend([0x35, 0x01, 0x48, 0x00]);
+        // No relocations, at 38.
+        script.extend(words(&[0]));
+
+        let mut heap = words(&[54, 0]);
+        // The class, at 4. The name pointer at 20 is relocated.
+        heap.extend(words(&[0x1234, 9, 4, 22, 0, 5, 0xFFFF, 0x8000, 42]));
+        // The instance, at 22. The name pointer at 38 is relocated.
+        heap.extend(words(&[0x1234, 9, 28, 28, 0, 5, 5, 0, 48]));
+        heap.extend(words(&[0]));
+        heap.extend(b"Thing\0thing\0");
+        heap.extend(words(&[2, 20, 38]));
+
+        (Block::from_vec(script), Block::from_vec(heap))
+    }
+
+    /// A script defining only the class `Other`, with species 6. It shares
+    /// its code and dictionaries with [`fixture_script`].
+    fn class_only_script() -> (Block, Block) {
+        let (script, _) = fixture_script();
+
+        let mut heap = words(&[30, 0]);
+        // The class, at 4. The name pointer at 20 is relocated.
+        heap.extend(words(&[0x1234, 9, 4, 22, 0, 6, 0xFFFF, 0x8000, 24]));
+        heap.extend(words(&[0]));
+        heap.extend(b"Other\0");
+        heap.extend(words(&[1, 20]));
+
+        (script, Block::from_vec(heap))
+    }
+
+    fn fixture_selectors() -> SelectorTable {
+        SelectorTable::from_names(&[
+            "-objID-",
+            "-size-",
+            "-propDict-",
+            "-methDict-",
+            "-classScript-",
+            "species",
+            "superClass",
+            "-info-",
+            "name",
+            "init",
+        ])
+    }
+
+    #[test]
+    fn methods_report_code_offsets() {
+        let (script, heap) = fixture_script();
+        let loaded = load_script(&script.narrow(), &heap.narrow()).unwrap();
+        for object in loaded.objects() {
+            assert_eq!(object.methods().collect::<Vec<_>>(), vec![(9, 34)]);
+            assert_eq!(object.method_offset(9), Some(34));
+            // `name` is a property, not a method defined by the object.
+            assert_eq!(object.method_offset(8), None);
+        }
+    }
+
+    #[test]
+    fn typed_values_use_property_names() {
+        let selectors = fixture_selectors();
+        let (script, heap) = fixture_script();
+        let loaded = load_script(&script.narrow(), &heap.narrow()).unwrap();
+        let class = &loaded.objects()[0];
+        assert_eq!(class.base_value(5), Some(5));
+        assert_eq!(
+            class.typed_value(5, &selectors),
+            Some(PropertyValue::UInt(5))
+        );
+        assert_eq!(class.typed_value(9, &selectors), None);
+
+        assert_eq!(classify_property(Some("x"), 0xFFFF), PropertyValue::Int(-1));
+        assert_eq!(
+            classify_property(Some("client"), 0x0120),
+            PropertyValue::ObjectRef(0x0120)
+        );
+        assert_eq!(classify_property(None, 0xFFFF), PropertyValue::UInt(0xFFFF));
+    }
+
+    #[test]
+    fn selector_references_list_every_class_using_selector() {
+        let selectors = fixture_selectors();
+        let (script, heap) = fixture_script();
+        let loaded = load_script(&script.narrow(), &heap.narrow()).unwrap();
+        let scripts = [(10, &loaded), (20, &loaded)];
+        let references = |name| {
+            selector_references(scripts, &selectors, name)
+                .into_iter()
+                .map(|(script_num, object)| (script_num, object.name().unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        // A method, and a property. The instance also defines `init`, but
+        // only classes are listed.
+        assert_eq!(references("init"), vec![(10, "Thing"), (20, "Thing")]);
+        assert_eq!(references("name"), vec![(10, "Thing"), (20, "Thing")]);
+        assert_eq!(references("nonexistent"), vec![]);
+    }
+
+    #[test]
+    fn is_abstract_checks_for_instances() {
+        let (script, heap) = fixture_script();
+        let with_instance = load_script(&script.narrow(), &heap.narrow()).unwrap();
+        let (script, heap) = class_only_script();
+        let class_only = load_script(&script.narrow(), &heap.narrow()).unwrap();
+        assert_eq!(class_only.objects()[0].name(), Some("Other"));
+
+        let scripts = [&with_instance, &class_only];
+        assert!(!is_abstract(scripts, 5));
+        assert!(is_abstract(scripts, 6));
+    }
+
+    #[test]
+    fn debug_dump_lists_objects_and_selectors() {
+        let selectors = fixture_selectors();
+        let (script, heap) = fixture_script();
+        let loaded = load_script(&script.narrow(), &heap.narrow()).unwrap();
+        let dump = loaded.debug_dump(&selectors);
+
+        assert!(
+            dump.contains("Class Thing: species 0005, super FFFF"),
+            "{}",
+            dump
+        );
+        assert!(
+            dump.contains("Object thing: species 0005, super 0005"),
+            "{}",
+            dump
+        );
+        assert!(dump.contains("    +0010 name = 0052"), "{}", dump);
+        assert!(dump.contains("    +0010 name = 0058"), "{}", dump);
+        assert_eq!(dump.matches("    init @ 0022").count(), 2, "{}", dump);
+    }
+}
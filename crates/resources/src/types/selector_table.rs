@@ -94,6 +94,27 @@ impl SelectorTable {
             })));
         }
 
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Creates a table holding the given selector names, in ID order.
+    ///
+    /// This is mainly useful for building fixtures in tests.
+    pub fn from_names(names: &[&str]) -> Self {
+        let entries = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                Selector(Arc::new(SelectorInner {
+                    name: SharedString::new(name.to_string()),
+                    id: id.try_into().unwrap(),
+                }))
+            })
+            .collect();
+        Self::from_entries(entries)
+    }
+
+    fn from_entries(entries: Vec<Selector>) -> Self {
         let mut reverse_entries = HashMap::new();
 
         for selector in entries.iter() {
@@ -102,10 +123,15 @@ impl SelectorTable {
                 .or_insert_with(Vec::new)
                 .push(selector.clone());
         }
-        Ok(Self {
+        Self {
             entries,
             reverse_entries,
-        })
+        }
+    }
+
+    /// Returns an iterator over all selectors in the table, in ID order.
+    pub fn selectors(&self) -> impl Iterator<Item = &Selector> {
+        self.entries.iter()
     }
 
     pub fn get_selector_by_id(&self, index: u16) -> Option<&Selector> {
@@ -118,3 +144,19 @@ impl SelectorTable {
             .and_then(|v| if v.len() == 1 { Some(&v[0]) } else { None })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_names_assigns_ids_in_order() {
+        let table = SelectorTable::from_names(&["x", "y"]);
+        assert_eq!(table.get_selector_by_name("y").unwrap().id(), 1);
+        assert_eq!(table.get_selector_by_id(0).unwrap().name(), "x");
+
+        let empty = SelectorTable::from_names(&[]);
+        assert_eq!(empty.selectors().count(), 0);
+        assert!(empty.get_selector_by_name("x").is_none());
+    }
+}
@@ -0,0 +1,162 @@
+//! Extracts the parser vocabulary from a resource library.
+//!
+//! In SCI1 and later, the main parser vocabulary is stored as Vocab:900.
+//! This is separate from the selector table in Vocab:997.
+
+use sci_utils::buffer::Buffer;
+
+/// The number of 16-bit letter offsets at the start of the vocabulary. We
+/// read words sequentially, so these are skipped.
+const NUM_LETTER_OFFSETS: usize = 255;
+
+/// A single word in the parser vocabulary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Word {
+    text: String,
+    class: u16,
+    group: u16,
+}
+
+impl Word {
+    /// The text of the word.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The word class bitmask (noun, verb, preposition, etc.).
+    pub fn class(&self) -> u16 {
+        self.class
+    }
+
+    /// The synonym group ID. Words with the same group are treated as the
+    /// same word by the parser.
+    pub fn group(&self) -> u16 {
+        self.group
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VocabTable {
+    words: Vec<Word>,
+}
+
+impl VocabTable {
+    pub fn load_from<'a, B: Buffer<'a, Idx = u16>>(data: B) -> anyhow::Result<Self> {
+        let data = data.as_ref();
+        let mut offset = NUM_LETTER_OFFSETS * 2;
+        anyhow::ensure!(
+            offset <= data.len(),
+            "Vocabulary resource is too short for its letter offset table"
+        );
+
+        // Each word shares a prefix with the previous word. The entry gives
+        // the length of the shared prefix, then the null-terminated suffix,
+        // and then three bytes packing the 12-bit class and group.
+        let mut words = Vec::new();
+        let mut curr_word: Vec<u8> = Vec::new();
+        while offset < data.len() {
+            let prefix_len = data[offset] as usize;
+            offset += 1;
+            anyhow::ensure!(
+                prefix_len <= curr_word.len(),
+                "Word at offset {:04X} shares {} bytes with a previous word of length {}",
+                offset - 1,
+                prefix_len,
+                curr_word.len()
+            );
+            curr_word.truncate(prefix_len);
+
+            let Some(null_pos) = data[offset..].iter().position(|&b| b == 0) else {
+                anyhow::bail!("No null terminator found for word at offset {:04X}", offset);
+            };
+            curr_word.extend_from_slice(&data[offset..][..null_pos]);
+            offset += null_pos + 1;
+
+            let Some(&[b0, b1, b2]) = data.get(offset..offset + 3) else {
+                anyhow::bail!("Truncated class/group for word at offset {:04X}", offset);
+            };
+            offset += 3;
+
+            words.push(Word {
+                text: String::from_utf8(curr_word.clone())?,
+                class: ((b0 as u16) << 4) | ((b1 as u16) >> 4),
+                group: (((b1 & 0x0F) as u16) << 8) | b2 as u16,
+            });
+        }
+
+        Ok(Self { words })
+    }
+
+    /// Returns an iterator over all words in the vocabulary, in the order
+    /// they are stored (alphabetical).
+    pub fn words(&self) -> impl Iterator<Item = &Word> {
+        self.words.iter()
+    }
+
+    /// Returns all entries with the given text. A word may appear more than
+    /// once if it belongs to several classes.
+    pub fn get_words_by_text<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a Word> {
+        self.words.iter().filter(move |word| word.text == text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab_resource(entries: &[(u8, &str, u16, u16)]) -> Vec<u8> {
+        let mut data = vec![0; NUM_LETTER_OFFSETS * 2];
+        for &(prefix_len, suffix, class, group) in entries {
+            data.push(prefix_len);
+            data.extend_from_slice(suffix.as_bytes());
+            data.push(0);
+            data.push((class >> 4) as u8);
+            data.push((((class & 0x0F) << 4) | (group >> 8)) as u8);
+            data.push((group & 0xFF) as u8);
+        }
+        data
+    }
+
+    #[test]
+    fn load_words_with_shared_prefixes() {
+        let data = vocab_resource(&[
+            (0, "door", 0x010, 0x123),
+            (0, "look", 0x080, 0x456),
+            (4, "ing", 0x020, 0x457),
+            (3, "se", 0x004, 0xABC),
+        ]);
+        let table = VocabTable::load_from(data.as_slice().narrow()).unwrap();
+
+        let words = table
+            .words()
+            .map(|word| (word.text(), word.class(), word.group()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            vec![
+                ("door", 0x010, 0x123),
+                ("look", 0x080, 0x456),
+                ("looking", 0x020, 0x457),
+                ("loose", 0x004, 0xABC),
+            ]
+        );
+
+        let looking = table.get_words_by_text("looking").collect::<Vec<_>>();
+        assert_eq!(looking.len(), 1);
+        assert_eq!(looking[0].class(), 0x020);
+        assert_eq!(table.get_words_by_text("window").count(), 0);
+    }
+
+    #[test]
+    fn load_fails_on_truncated_word() {
+        let mut data = vocab_resource(&[(0, "door", 0x010, 0x123)]);
+        data.truncate(data.len() - 1);
+        assert!(VocabTable::load_from(data.as_slice().narrow()).is_err());
+    }
+
+    #[test]
+    fn load_fails_on_invalid_prefix() {
+        let data = vocab_resource(&[(3, "door", 0x010, 0x123)]);
+        assert!(VocabTable::load_from(data.as_slice().narrow()).is_err());
+    }
+}
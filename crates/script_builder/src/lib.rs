@@ -61,7 +61,7 @@ struct LocalClassDef {
 struct LocalObjectDef {
     parent: ClassRef,
     name: Option<StringRef>,
-    properties: Vec<Value>,
+    properties: Vec<PropertyRef>,
     methods: Vec<MethodDef>,
 }
 
@@ -71,10 +71,38 @@ enum ClassDef {
     Species(u16),
 }
 
+/// An error from adding an invalid definition to a [`ScriptBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptBuildError {
+    /// A string passed as text had non-ASCII characters, whose encoding in
+    /// the game is not known.
+    NonAsciiString(String),
+    /// A string had a null byte at the given offset, which would end the
+    /// string early.
+    EmbeddedNull { offset: usize },
+}
+
+impl std::fmt::Display for ScriptBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptBuildError::NonAsciiString(string) => {
+                write!(f, "String {:?} is not ASCII", string)
+            }
+            ScriptBuildError::EmbeddedNull { offset } => {
+                write!(f, "String has a null byte at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptBuildError {}
+
 pub struct ScriptBuilder {
     strings: WeakSymbolMap<RelocatableBuffer>,
     exports: WeakSymbolMap<Export>,
     class_defs: WeakSymbolMap<ClassDef>,
+    local_classes: WeakSymbolMap<LocalClassDef>,
+    local_objects: WeakSymbolMap<LocalObjectDef>,
     local_functions: WeakSymbolMap<FunctionDef>,
 }
 
@@ -96,22 +124,95 @@ impl ExportBuilder<'_> {
     }
 }
 
+pub struct ClassBuilder<'a> {
+    class_ref: &'a mut LocalClassDef,
+}
+
+impl ClassBuilder<'_> {
+    pub fn set_parent(&mut self, parent: ClassRef) {
+        self.class_ref.parent = Some(parent);
+    }
+
+    pub fn clear_parent(&mut self) {
+        self.class_ref.parent = None;
+    }
+
+    pub fn set_name(&mut self, name: StringRef) {
+        self.class_ref.name = Some(name);
+    }
+
+    /// Sets the species number of the class. If unset, one is assigned when
+    /// the script is linked.
+    pub fn set_species(&mut self, species: u16) {
+        self.class_ref.species = Some(species);
+    }
+
+    pub fn clear_species(&mut self) {
+        self.class_ref.species = None;
+    }
+
+    pub fn add_property(&mut self, name: SelectorRef, value: Value) {
+        self.class_ref.properties.push(PropertyRef { name, value });
+    }
+
+    pub fn add_method(&mut self, name: SelectorRef, function: FunctionRef) {
+        self.class_ref.methods.push(MethodDef { name, function });
+    }
+}
+
+pub struct ObjectBuilder<'a> {
+    object_ref: &'a mut LocalObjectDef,
+}
+
+impl ObjectBuilder<'_> {
+    pub fn set_name(&mut self, name: StringRef) {
+        self.object_ref.name = Some(name);
+    }
+
+    pub fn add_property(&mut self, name: SelectorRef, value: Value) {
+        self.object_ref.properties.push(PropertyRef { name, value });
+    }
+
+    pub fn add_method(&mut self, name: SelectorRef, function: FunctionRef) {
+        self.object_ref.methods.push(MethodDef { name, function });
+    }
+}
+
 impl ScriptBuilder {
     pub fn new() -> Self {
         Self {
             strings: WeakSymbolMap::new(),
             exports: WeakSymbolMap::new(),
             class_defs: WeakSymbolMap::new(),
+            local_classes: WeakSymbolMap::new(),
+            local_objects: WeakSymbolMap::new(),
             local_functions: WeakSymbolMap::new(),
         }
     }
 
-    pub fn add_string(&mut self, string: &str) -> StringRef {
-        assert!(string.is_ascii());
-        let string_sym = Symbol::with_name(format!("script string {:?}", string));
+    /// Adds an ASCII string to the script. Strings with other characters
+    /// must be encoded for the game by the caller, and added with
+    /// [`ScriptBuilder::add_string_bytes`].
+    pub fn add_string(&mut self, string: &str) -> Result<StringRef, ScriptBuildError> {
+        if !string.is_ascii() {
+            return Err(ScriptBuildError::NonAsciiString(string.to_string()));
+        }
+        self.add_string_bytes(string.as_bytes())
+    }
+
+    /// Adds a string that is already encoded in the game's code page. A null
+    /// terminator is added, so the string may not contain null bytes itself.
+    pub fn add_string_bytes(&mut self, bytes: &[u8]) -> Result<StringRef, ScriptBuildError> {
+        if let Some(offset) = bytes.iter().position(|&b| b == 0) {
+            return Err(ScriptBuildError::EmbeddedNull { offset });
+        }
+        let string_sym = Symbol::with_name(format!(
+            "script string {:?}",
+            String::from_utf8_lossy(bytes)
+        ));
         let mut reloc_builder = RelocatableBuffer::builder();
         reloc_builder.mark_symbol(string_sym.clone());
-        reloc_builder.write_bytes(string.as_bytes());
+        reloc_builder.write_bytes(bytes);
         reloc_builder.write_u8(0);
         self.strings.insert(
             &string_sym,
@@ -119,7 +220,7 @@ impl ScriptBuilder {
                 .build()
                 .expect("String buffer builds successfully"),
         );
-        StringRef(string_sym)
+        Ok(StringRef(string_sym))
     }
 
     pub fn add_export(&mut self, value: Value) -> (ExportRef, ExportBuilder) {
@@ -135,8 +236,53 @@ impl ScriptBuilder {
         (ExportRef(export_sym), builder)
     }
 
+    /// Adds a class defined in this script. The class starts with no parent,
+    /// name, species, properties or methods.
+    pub fn add_class(&mut self) -> (ClassRef, ClassBuilder<'_>) {
+        let class_sym = Symbol::new();
+        let class_def = LocalClassDef {
+            parent: None,
+            name: None,
+            species: None,
+            properties: Vec::new(),
+            methods: Vec::new(),
+        };
+        let builder = match self.local_classes.try_insert_mut(&class_sym, class_def) {
+            Ok(class_ref) => ClassBuilder { class_ref },
+            Err(_) => panic!("Class symbol already exists"),
+        };
+        (ClassRef(class_sym), builder)
+    }
+
+    /// Adds an instance of `parent` defined in this script.
+    pub fn add_object(&mut self, parent: ClassRef) -> (ObjectRef, ObjectBuilder<'_>) {
+        let object_sym = Symbol::new();
+        let object_def = LocalObjectDef {
+            parent,
+            name: None,
+            properties: Vec::new(),
+            methods: Vec::new(),
+        };
+        let builder = match self.local_objects.try_insert_mut(&object_sym, object_def) {
+            Ok(object_ref) => ObjectBuilder { object_ref },
+            Err(_) => panic!("Object symbol already exists"),
+        };
+        (ObjectRef(object_sym), builder)
+    }
+
+    /// Declares a class defined by another script, referenced by its species
+    /// number. Declaring the same species again returns the same class.
     pub fn declare_class_species(&mut self, species: u16) -> ClassRef {
-        todo!()
+        let existing = (&self.class_defs)
+            .into_iter()
+            .find(|(_, class_def)| matches!(class_def, ClassDef::Species(s) if *s == species));
+        if let Some((class_sym, _)) = existing {
+            return ClassRef(class_sym);
+        }
+        let class_sym = Symbol::with_name(format!("class species {}", species));
+        self.class_defs
+            .insert(&class_sym, ClassDef::Species(species));
+        ClassRef(class_sym)
     }
 }
 
@@ -145,3 +291,70 @@ impl Default for ScriptBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_species_are_declared_once() {
+        let mut builder = ScriptBuilder::new();
+        let first = builder.declare_class_species(5);
+        let again = builder.declare_class_species(5);
+        let other = builder.declare_class_species(6);
+        assert_eq!(first.0, again.0);
+        assert_ne!(first.0, other.0);
+        assert_eq!(builder.class_defs.len(), 2);
+    }
+
+    #[test]
+    fn invalid_strings_are_rejected() {
+        let mut builder = ScriptBuilder::new();
+        // Encoded text outside of ASCII is accepted as bytes.
+        let string = builder.add_string_bytes(b"Caf\x82").unwrap();
+        assert!(builder.strings.contains_key(&string.0));
+        builder.strings.clear();
+
+        assert_eq!(
+            builder.add_string("Café").err(),
+            Some(ScriptBuildError::NonAsciiString("Café".to_string()))
+        );
+        assert_eq!(
+            builder.add_string_bytes(b"ab\0cd").err(),
+            Some(ScriptBuildError::EmbeddedNull { offset: 2 })
+        );
+        assert!(builder.strings.is_empty());
+    }
+
+    #[test]
+    fn objects_and_classes_record_definitions() {
+        let mut builder = ScriptBuilder::new();
+        let name = builder.add_string("Door").unwrap();
+        let selector = SelectorRef(Symbol::new());
+        let function = FunctionRef(Symbol::new());
+
+        let (class, mut class_builder) = builder.add_class();
+        class_builder.set_species(40);
+        class_builder.add_property(selector.clone(), Value::Int16(3));
+        class_builder.add_method(selector.clone(), function.clone());
+
+        let (object, mut object_builder) = builder.add_object(class.clone());
+        object_builder.set_name(name);
+        object_builder.add_property(selector, Value::Int16(7));
+
+        let class_def = builder.local_classes.get(&class.0).unwrap();
+        assert_eq!(class_def.species, Some(40));
+        assert_eq!(class_def.properties.len(), 1);
+        assert_eq!(class_def.methods.len(), 1);
+        let object_def = builder.local_objects.get(&object.0).unwrap();
+        assert_eq!(object_def.parent.0, class.0);
+        assert!(object_def.name.is_some());
+        assert!(matches!(
+            object_def.properties[..],
+            [PropertyRef {
+                value: Value::Int16(7),
+                ..
+            }]
+        ));
+    }
+}
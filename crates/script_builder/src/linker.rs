@@ -85,5 +85,4 @@ pub struct OriginalGameInfo {
 pub struct ScriptSymbols {
     script_num: u16,
     script_name: String,
-    
-}
\ No newline at end of file
+}
@@ -68,6 +68,27 @@ impl From<io::Error> for ReadError {
 /// The result of a read operation.
 pub type ReadResult<T> = std::result::Result<T, ReadError>;
 
+/// The character encoding of strings stored in a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodePage {
+    /// ISO 8859-1. Every byte decodes to the code point of the same value, so
+    /// this also covers plain ASCII.
+    #[default]
+    Latin1,
+    /// UTF-8. Invalid sequences are reported as errors.
+    Utf8,
+}
+
+impl CodePage {
+    fn decode(self, bytes: &[u8]) -> io::Result<String> {
+        match self {
+            CodePage::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+            CodePage::Utf8 => String::from_utf8(bytes.to_vec())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+    }
+}
+
 /// An in-memory block of data that is cheap to clone, and create subranges of.
 #[derive(Clone)]
 pub struct Block {
@@ -118,6 +139,57 @@ impl Block {
         Ok(())
     }
 
+    /// Returns the contents of the block as a slice, without copying.
+    ///
+    /// Blocks are always backed by a single contiguous in-memory buffer, so
+    /// this cannot fail.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[self.start..][..self.size]
+    }
+
+    /// Copies the contents of the block into a new vector.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_slice().to_vec()
+    }
+
+    /// Writes the contents of the block to the writer, returning the number
+    /// of bytes written.
+    ///
+    /// The contents are written straight from the shared buffer, without
+    /// an intermediate copy.
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<u64> {
+        w.write_all(self.as_slice())?;
+        Ok(self.size as u64)
+    }
+
+    /// Reads a NUL-terminated string starting at `offset`, decoded as
+    /// Latin-1.
+    ///
+    /// Returns an error if no terminator is found before the end of the block.
+    pub fn read_cstr_at(&self, offset: usize) -> io::Result<String> {
+        self.read_cstr_at_with(offset, CodePage::default())
+    }
+
+    /// Reads a NUL-terminated string starting at `offset`, decoded with the
+    /// given code page.
+    ///
+    /// Returns an error if no terminator is found before the end of the block.
+    pub fn read_cstr_at_with(&self, offset: usize, code_page: CodePage) -> io::Result<String> {
+        let data = self.as_slice().get(offset..).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "String offset is past the end of the block",
+            )
+        })?;
+        let null_pos = data.iter().position(|&b| b == 0).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "No null terminator found in string",
+            )
+        })?;
+        code_page.decode(&data[..null_pos])
+    }
+
     /// Read the entirety of the buffer into a vector.
     pub fn read_all(&self) -> ReadResult<Vec<u8>> {
         let mut buf = vec![0; self.size];
@@ -141,13 +213,13 @@ impl std::ops::Deref for Block {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.data[self.start..][..self.size]
+        self.as_slice()
     }
 }
 
 impl AsRef<[u8]> for Block {
     fn as_ref(&self) -> &[u8] {
-        &self.data[self.start..][..self.size]
+        self.as_slice()
     }
 }
 
@@ -472,7 +544,7 @@ impl DataReader for BlockReader {
         let mut buf = [0; 3];
         self.block.read_at(self.curr_pos, &mut buf)?;
         self.curr_pos += 3;
-        Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], 0]))
+        Ok(crate::numbers::from_le_bytes_u24(buf))
     }
 
     fn read_u32_le(&mut self) -> io::Result<u32> {
@@ -512,3 +584,78 @@ impl DataReader for BlockReader {
             .map_err(ReadError::from_std_err)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_slice_returns_block_contents() {
+        let block = Block::from_vec(vec![1, 2, 3, 4, 5]);
+        assert_eq!(block.as_slice(), &[1, 2, 3, 4, 5]);
+        assert_eq!(block.to_vec(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn as_slice_respects_sub_block_range() {
+        let block = Block::from_vec(vec![1, 2, 3, 4, 5]);
+        let sub_block = block.clone().sub_buffer(1..4);
+        assert_eq!(sub_block.as_slice(), &[2, 3, 4]);
+        assert_eq!(sub_block.to_vec(), sub_block.read_all().unwrap());
+
+        // The slice should point into the original buffer, not a copy.
+        assert!(std::ptr::eq(&sub_block.as_slice()[0], &block.as_slice()[1]));
+    }
+
+    #[test]
+    fn read_cstr_at_reads_up_to_terminator() {
+        let block = Block::from_vec(b"xxHello\0World\0".to_vec());
+        assert_eq!(block.read_cstr_at(2).unwrap(), "Hello");
+        assert_eq!(block.read_cstr_at(8).unwrap(), "World");
+    }
+
+    #[test]
+    fn read_cstr_at_reads_empty_string() {
+        let block = Block::from_vec(b"abc\0".to_vec());
+        assert_eq!(block.read_cstr_at(3).unwrap(), "");
+    }
+
+    #[test]
+    fn read_cstr_at_fails_on_unterminated_string() {
+        let block = Block::from_vec(b"abc\0def".to_vec());
+        let err = block.read_cstr_at(4).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        // The terminator must lie within the block, even if the underlying
+        // buffer continues past it.
+        let sub_block = Block::from_vec(b"abc\0".to_vec()).sub_buffer(0..3);
+        assert!(sub_block.read_cstr_at(0).is_err());
+        assert!(block.read_cstr_at(100).is_err());
+    }
+
+    #[test]
+    fn read_cstr_at_decodes_with_code_page() {
+        let block = Block::from_vec(vec![b'c', 0xE9, 0]);
+        assert_eq!(block.read_cstr_at(0).unwrap(), "c\u{E9}");
+        assert!(block.read_cstr_at_with(0, CodePage::Utf8).is_err());
+
+        let block = Block::from_vec("c\u{E9}\0".as_bytes().to_vec());
+        assert_eq!(
+            block.read_cstr_at_with(0, CodePage::Utf8).unwrap(),
+            "c\u{E9}"
+        );
+    }
+
+    #[test]
+    fn write_to_writes_only_block_range() {
+        let block = Block::from_vec((0u8..100).collect());
+        let sub_block = block.clone().sub_buffer(10..30);
+
+        let mut out = Vec::new();
+        assert_eq!(block.write_to(&mut out).unwrap(), 100);
+        assert_eq!(sub_block.write_to(&mut out).unwrap(), 20);
+
+        let mut expected = (0u8..100).collect::<Vec<_>>();
+        expected.extend(10u8..30);
+        assert_eq!(out, expected);
+    }
+}
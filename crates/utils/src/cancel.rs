@@ -0,0 +1,124 @@
+//! Cooperative cancellation of long running operations.
+//!
+//! A [`CancellationToken`] is checked by the operation at convenient points,
+//! and cancelled by whoever started it. Tokens can form a hierarchy, so a
+//! whole tree of operations can be cancelled at once while still allowing
+//! each branch to be cancelled on its own.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+struct TokenState {
+    cancelled: AtomicBool,
+    parent: Option<Arc<TokenState>>,
+}
+
+impl TokenState {
+    fn is_cancelled(&self) -> bool {
+        let mut state = self;
+        loop {
+            if state.cancelled.load(Ordering::Acquire) {
+                return true;
+            }
+            match &state.parent {
+                Some(parent) => state = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// A shared flag requesting that an operation stop early.
+///
+/// Clones of a token share the same state, so cancelling any clone cancels
+/// them all.
+#[derive(Clone)]
+pub struct CancellationToken(Arc<TokenState>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken(Arc::new(TokenState {
+            cancelled: AtomicBool::new(false),
+            parent: None,
+        }))
+    }
+
+    /// Requests cancellation of this token and all of its children.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns true if this token or any of its ancestors has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    /// Creates a token that is cancelled when this one is, but that can also
+    /// be cancelled on its own without affecting this token.
+    pub fn child(&self) -> Self {
+        CancellationToken(Arc::new(TokenState {
+            cancelled: AtomicBool::new(false),
+            parent: Some(self.0.clone()),
+        }))
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_is_seen_by_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[test]
+    fn cancel_propagates_to_children_only() {
+        let root = CancellationToken::new();
+        let child = root.child();
+        let grandchild = child.child();
+        let sibling = root.child();
+
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(grandchild.is_cancelled());
+        assert!(!root.is_cancelled());
+        assert!(!sibling.is_cancelled());
+
+        root.cancel();
+        assert!(sibling.is_cancelled());
+    }
+
+    #[test]
+    fn cancel_is_seen_across_threads() {
+        let token = CancellationToken::new();
+        let child = token.child();
+        let worker = std::thread::spawn(move || {
+            while !child.is_cancelled() {
+                std::thread::yield_now();
+            }
+        });
+        token.cancel();
+        worker.join().unwrap();
+    }
+}
@@ -1,2 +1,3 @@
 pub mod dcl;
 mod huffman;
+pub mod lzw;
@@ -1,4 +1,7 @@
-use std::io;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
 
 use bitter::BitReader;
 
@@ -6,12 +9,24 @@ use crate::block::Block;
 
 use super::huffman::{ASCII_TREE, DISTANCE_TREE, LENGTH_TREE};
 
+/// The number of decompressed bytes to collect before passing them on to the
+/// output writer.
+const OUTPUT_CHUNK_SIZE: usize = 4096;
+
+/// Decompresses DCL-compressed data into a new block.
 pub fn decompress_dcl(input: &Block) -> io::Result<Block> {
+    let mut output = Vec::with_capacity(input.size().checked_mul(2).unwrap());
+    decompress_dcl_to_writer(input.as_slice(), &mut output)?;
+    Ok(Block::from_vec(output))
+}
+
+/// Decompresses DCL-compressed data, writing the result to `out` as it is
+/// produced. Returns the number of bytes written.
+pub fn decompress_dcl_to_writer<W: io::Write>(input: &[u8], out: &mut W) -> io::Result<u64> {
     // This follows the implementation from ScummVM, in DecompressorDCL::unpack()
-    let input_data = input.read_all()?;
-    let input_size = input_data.len();
-    let mut reader = bitter::LittleEndianReader::new(&input_data);
-    let mut output = Vec::with_capacity(input_size.checked_mul(2).unwrap());
+    let mut reader = bitter::LittleEndianReader::new(input);
+    let mut output = Vec::with_capacity(OUTPUT_CHUNK_SIZE);
+    let mut bytes_written: u64 = 0;
     let Some(mode) = reader.read_u8() else {
         return Err(io::Error::other("Failed to read DCL mode"));
     };
@@ -39,6 +54,11 @@ pub fn decompress_dcl(input: &Block) -> io::Result<Block> {
     let mut dict_pos: u32 = 0;
 
     loop {
+        if output.len() >= OUTPUT_CHUNK_SIZE {
+            out.write_all(&output)?;
+            bytes_written += output.len() as u64;
+            output.clear();
+        }
         let should_decode_entry = reader
             .read_bit()
             .ok_or_else(|| io::Error::other("Failed to read DCL entry type"))?;
@@ -74,7 +94,7 @@ pub fn decompress_dcl(input: &Block) -> io::Result<Block> {
                             io::Error::other("Failed to read DCL extra distance bits")
                         })? as u32
                 };
-            if output.len() < token_offset as usize {
+            if bytes_written + (output.len() as u64) < token_offset as u64 {
                 return Err(io::Error::other("DCL token offset exceeds bytes written"));
             }
 
@@ -114,5 +134,258 @@ pub fn decompress_dcl(input: &Block) -> io::Result<Block> {
         }
     }
 
-    Ok(Block::from_vec(output))
+    out.write_all(&output)?;
+    bytes_written += output.len() as u64;
+    Ok(bytes_written)
+}
+
+/// The dictionary type written by `compress_dcl`, giving a 4096 byte
+/// dictionary.
+const COMPRESS_DICT_TYPE: u8 = 6;
+/// The shortest match `compress_dcl` will encode. Shorter matches rarely
+/// save any space over literals.
+const MIN_MATCH_LENGTH: usize = 3;
+/// The longest encodable match. A length of 519 marks the end of the stream.
+const MAX_MATCH_LENGTH: usize = 518;
+/// The number of earlier positions checked when looking for a match.
+const MAX_MATCH_CANDIDATES: usize = 16;
+
+/// Writes bits in the order `bitter::LittleEndianReader` reads them.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    num_bits: usize,
+}
+
+impl BitWriter {
+    fn push_bit(&mut self, bit: bool) {
+        if self.num_bits.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (self.num_bits % 8);
+        }
+        self.num_bits += 1;
+    }
+
+    /// Writes the low `count` bits of `value`, least significant first.
+    fn push_bits(&mut self, value: u32, count: u32) {
+        for i in 0..count {
+            self.push_bit(value & (1 << i) != 0);
+        }
+    }
+
+    fn push_code(&mut self, code: &[bool]) {
+        for &bit in code {
+            self.push_bit(bit);
+        }
+    }
+}
+
+/// Encodes DCL tokens, the inverse of the token decoding in
+/// `decompress_dcl_to_writer`.
+struct TokenWriter {
+    bits: BitWriter,
+    length_codes: BTreeMap<u8, Vec<bool>>,
+    distance_codes: BTreeMap<u8, Vec<bool>>,
+}
+
+impl TokenWriter {
+    fn new() -> Self {
+        let mut bits = BitWriter::default();
+        // Binary mode, so literals are written as plain bytes.
+        bits.push_bits(0, 8);
+        bits.push_bits(COMPRESS_DICT_TYPE as u32, 8);
+        TokenWriter {
+            bits,
+            length_codes: LENGTH_TREE.codes(),
+            distance_codes: DISTANCE_TREE.codes(),
+        }
+    }
+
+    fn literal(&mut self, value: u8) {
+        self.bits.push_bit(false);
+        self.bits.push_bits(value as u32, 8);
+    }
+
+    fn length(&mut self, length: u32) {
+        self.bits.push_bit(true);
+        if length < 10 {
+            self.bits
+                .push_code(&self.length_codes[&((length - 2) as u8)]);
+        } else {
+            let num_bits = 31 - (length - 8).leading_zeros();
+            self.bits
+                .push_code(&self.length_codes[&((num_bits + 7) as u8)]);
+            self.bits.push_bits(length - 8 - (1 << num_bits), num_bits);
+        }
+    }
+
+    fn back_reference(&mut self, length: usize, offset: usize) {
+        self.length(length as u32);
+        let value = (offset - 1) as u32;
+        let low_bits = if length == 2 {
+            2
+        } else {
+            COMPRESS_DICT_TYPE as u32
+        };
+        self.bits
+            .push_code(&self.distance_codes[&((value >> low_bits) as u8)]);
+        self.bits.push_bits(value, low_bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.length(519);
+        self.bits.bytes
+    }
+}
+
+/// Compresses data with DCL, in a form `decompress_dcl` accepts.
+///
+/// This favors simplicity over compression ratio: it only looks for matches
+/// among a few recent positions sharing the same three-byte prefix.
+pub fn compress_dcl(input: &[u8]) -> Vec<u8> {
+    let dict_size = 1usize << (COMPRESS_DICT_TYPE + 6);
+    let mut writer = TokenWriter::new();
+    let mut positions: HashMap<&[u8], Vec<usize>> = HashMap::new();
+
+    let mut pos = 0;
+    while pos < input.len() {
+        let max_length = MAX_MATCH_LENGTH.min(input.len() - pos);
+        let best = input
+            .get(pos..pos + MIN_MATCH_LENGTH)
+            .and_then(|key| positions.get(key))
+            .into_iter()
+            .flat_map(|candidates| candidates.iter().rev().take(MAX_MATCH_CANDIDATES))
+            .take_while(|&&start| pos - start < dict_size)
+            .map(|&start| {
+                // Matches may overlap the current position, as the decoder
+                // copies one byte at a time.
+                let length = (0..max_length)
+                    .take_while(|&i| input[start + i] == input[pos + i])
+                    .count();
+                (length, pos - start)
+            })
+            .max_by_key(|&(length, offset)| (length, std::cmp::Reverse(offset)));
+
+        let advance = match best {
+            Some((length, offset)) if length >= MIN_MATCH_LENGTH => {
+                writer.back_reference(length, offset);
+                length
+            }
+            _ => {
+                writer.literal(input[pos]);
+                1
+            }
+        };
+        for covered in pos..pos + advance {
+            if let Some(key) = input.get(covered..covered + MIN_MATCH_LENGTH) {
+                positions.entry(key).or_default().push(covered);
+            }
+        }
+        pos += advance;
+    }
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packs bits into bytes, least significant bit first.
+    fn pack_bits(bits: &[bool]) -> Vec<u8> {
+        bits.chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |byte, (i, &bit)| byte | (u8::from(bit) << i))
+            })
+            .collect()
+    }
+
+    fn byte_bits(value: u8) -> impl Iterator<Item = bool> {
+        (0..8).map(move |i| value & (1 << i) != 0)
+    }
+
+    /// Builds a binary-mode DCL stream containing only literal bytes.
+    fn literal_stream(data: &[u8], terminate: bool) -> Vec<u8> {
+        let mut bits = Vec::new();
+        // Mode 0 (binary), dictionary type 4 (1024 bytes).
+        bits.extend(byte_bits(0));
+        bits.extend(byte_bits(4));
+        for &value in data {
+            bits.push(false);
+            bits.extend(byte_bits(value));
+        }
+        if terminate {
+            // A token whose length is 519 marks the end of the stream. That
+            // is length code 15 (seven zero bits) with all extra bits set.
+            bits.push(true);
+            bits.extend([false; 7]);
+            bits.extend([true; 8]);
+        }
+        pack_bits(&bits)
+    }
+
+    #[test]
+    fn decompresses_literals_to_writer() {
+        let data = (0..10_000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let mut out = Vec::new();
+        let written = decompress_dcl_to_writer(&literal_stream(&data, true), &mut out).unwrap();
+        assert_eq!(written, data.len() as u64);
+        assert_eq!(out, data);
+
+        let block = decompress_dcl(&Block::from_vec(literal_stream(&data, true))).unwrap();
+        assert_eq!(block.as_slice(), &data[..]);
+    }
+
+    #[test]
+    fn fails_on_truncated_stream() {
+        let mut out = Vec::new();
+        assert!(decompress_dcl_to_writer(&literal_stream(b"abc", false), &mut out).is_err());
+    }
+
+    /// Generates deterministic pseudo-random data, with `alphabet` distinct
+    /// byte values so that repeats occur.
+    fn pseudo_random(len: usize, alphabet: u32, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((state >> 16) % alphabet) as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        decompress_dcl(&Block::from_vec(compress_dcl(data)))
+            .unwrap()
+            .to_vec()
+    }
+
+    #[test]
+    fn compress_round_trips() {
+        let mut inputs = vec![
+            Vec::new(),
+            vec![42],
+            b"abcabcabcabcabc".to_vec(),
+            vec![7; 5000],
+            (0..=255).collect(),
+        ];
+        for (seed, alphabet) in [(1, 2), (2, 4), (3, 16), (4, 256)] {
+            for len in [1, 2, 3, 100, 5000, 20_000] {
+                inputs.push(pseudo_random(len, alphabet, seed));
+            }
+        }
+        for input in inputs {
+            assert_eq!(round_trip(&input), input, "length {}", input.len());
+        }
+    }
+
+    #[test]
+    fn compress_shrinks_repetitive_data() {
+        let data = b"The quick brown fox. ".repeat(200);
+        assert!(compress_dcl(&data).len() < data.len() / 10);
+    }
 }
@@ -1,4 +1,4 @@
-use std::io;
+use std::{collections::BTreeMap, io};
 
 use bitter::BitReader;
 
@@ -12,6 +12,32 @@ pub struct HuffmanTable<T> {
 }
 
 impl<T> HuffmanTable<T> {
+    /// Returns the bit sequence that `lookup` decodes to each value in the
+    /// table, indexed by value.
+    pub fn codes(&self) -> BTreeMap<T, Vec<bool>>
+    where
+        T: Ord + Clone,
+    {
+        let mut codes = BTreeMap::new();
+        let mut pending = vec![(0, Vec::new())];
+        while let Some((pos, path)) = pending.pop() {
+            match &self.entries[pos] {
+                HuffmanTableEntry::Leaf(value) => {
+                    codes.insert(value.clone(), path);
+                }
+                HuffmanTableEntry::Branch(left, right) => {
+                    let mut left_path = path.clone();
+                    left_path.push(false);
+                    pending.push((*left, left_path));
+                    let mut right_path = path;
+                    right_path.push(true);
+                    pending.push((*right, right_path));
+                }
+            }
+        }
+        codes
+    }
+
     pub fn lookup(&self, reader: &mut bitter::LittleEndianReader) -> io::Result<&T> {
         let mut pos = 0;
         loop {
@@ -0,0 +1,259 @@
+use std::io;
+
+use bitter::BitReader;
+
+use crate::block::Block;
+
+/// Resets the dictionary and the code width.
+const RESET_CODE: u16 = 0x100;
+/// Marks the end of the compressed data.
+const END_CODE: u16 = 0x101;
+/// The first code assigned to a dictionary entry.
+const FIRST_TOKEN: u16 = 0x102;
+const MIN_BITS: u32 = 9;
+const MAX_BITS: u32 = 12;
+
+/// Decompresses data using SCI0's LZW compression (compression type 1).
+pub fn decompress_lzw(input: &Block) -> io::Result<Block> {
+    let reader = bitter::LittleEndianReader::new(input.as_slice());
+    Ok(Block::from_vec(decompress(reader, false)?))
+}
+
+/// Decompresses data using SCI01's LZW1 compression (compression type 2).
+///
+/// This differs from [`decompress_lzw`] in reading codes most significant
+/// bit first, and in widening codes one entry earlier.
+pub fn decompress_lzw1(input: &Block) -> io::Result<Block> {
+    let reader = bitter::BigEndianReader::new(input.as_slice());
+    Ok(Block::from_vec(decompress(reader, true)?))
+}
+
+/// A dictionary entry: the entry for the string without its last byte, and
+/// that last byte.
+#[derive(Clone, Copy)]
+struct Token {
+    prefix: u16,
+    last: u8,
+}
+
+fn decompress<R: BitReader>(mut reader: R, early_change: bool) -> io::Result<Vec<u8>> {
+    // This follows the implementations from ScummVM, in
+    // DecompressorLZW::unpackLZW() and DecompressorLZW::unpackLZW1()
+    let mut output = Vec::new();
+    let mut tokens = vec![Token { prefix: 0, last: 0 }; 1 << MAX_BITS];
+    let mut stack = Vec::new();
+    let mut num_bits = MIN_BITS;
+    let mut next_token = FIRST_TOKEN;
+    let mut prev: Option<u16> = None;
+    let mut prev_first = 0u8;
+
+    // As in the interpreter, running out of input ends the data.
+    while let Some(code) = reader.read_bits(num_bits) {
+        let code = code as u16;
+        match code {
+            END_CODE => break,
+            RESET_CODE => {
+                num_bits = MIN_BITS;
+                next_token = FIRST_TOKEN;
+                prev = None;
+                continue;
+            }
+            _ => {}
+        }
+
+        let Some(prev_code) = prev else {
+            if code > 0xFF {
+                return Err(io::Error::other(format!(
+                    "LZW stream starts with undefined code {:03X}",
+                    code
+                )));
+            }
+            output.push(code as u8);
+            prev = Some(code);
+            prev_first = code as u8;
+            continue;
+        };
+
+        // A code one past the end of the dictionary is the entry about to be
+        // added, which is the previous string followed by its own first byte.
+        let mut token = if code < next_token {
+            code
+        } else if code == next_token {
+            stack.push(prev_first);
+            prev_code
+        } else {
+            return Err(io::Error::other(format!(
+                "LZW code {:03X} is past the end of the dictionary",
+                code
+            )));
+        };
+        while token > 0xFF {
+            let entry = tokens[token as usize];
+            stack.push(entry.last);
+            token = entry.prefix;
+        }
+        let first = token as u8;
+        stack.push(first);
+        output.extend(stack.drain(..).rev());
+
+        if (next_token as usize) < tokens.len() {
+            tokens[next_token as usize] = Token {
+                prefix: prev_code,
+                last: first,
+            };
+            next_token += 1;
+        }
+        let end_token = (1u16 << num_bits) - 1;
+        let should_widen = if early_change {
+            next_token == end_token
+        } else {
+            next_token > end_token
+        };
+        if should_widen && num_bits < MAX_BITS {
+            num_bits += 1;
+        }
+
+        prev = Some(code);
+        prev_first = first;
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packs `(code, width)` pairs into bytes, least significant bit first.
+    fn pack_lsb(codes: &[(u16, u32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut acc = 0u32;
+        let mut num_bits = 0;
+        for &(code, width) in codes {
+            acc |= (code as u32) << num_bits;
+            num_bits += width;
+            while num_bits >= 8 {
+                bytes.push(acc as u8);
+                acc >>= 8;
+                num_bits -= 8;
+            }
+        }
+        if num_bits > 0 {
+            bytes.push(acc as u8);
+        }
+        bytes
+    }
+
+    /// Packs `(code, width)` pairs into bytes, most significant bit first.
+    fn pack_msb(codes: &[(u16, u32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut acc = 0u32;
+        let mut num_bits = 0;
+        for &(code, width) in codes {
+            acc = (acc << width) | code as u32;
+            num_bits += width;
+            while num_bits >= 8 {
+                bytes.push((acc >> (num_bits - 8)) as u8);
+                num_bits -= 8;
+            }
+        }
+        if num_bits > 0 {
+            bytes.push((acc << (8 - num_bits)) as u8);
+        }
+        bytes
+    }
+
+    fn nine_bit(codes: &[u16]) -> Vec<(u16, u32)> {
+        codes.iter().map(|&code| (code, 9)).collect()
+    }
+
+    // "TOBEORNOTTOBEORTOBEORNOT", the classic LZW example, with the
+    // dictionary starting at 0x102.
+    const EXAMPLE_CODES: &[u16] = &[
+        b'T' as u16,
+        b'O' as u16,
+        b'B' as u16,
+        b'E' as u16,
+        b'O' as u16,
+        b'R' as u16,
+        b'N' as u16,
+        b'O' as u16,
+        b'T' as u16,
+        0x102,
+        0x104,
+        0x106,
+        0x10B,
+        0x105,
+        0x107,
+        0x109,
+        END_CODE,
+    ];
+    const EXAMPLE_TEXT: &[u8] = b"TOBEORNOTTOBEORTOBEORNOT";
+
+    #[test]
+    fn decompresses_lzw_example() {
+        let input = Block::from_vec(pack_lsb(&nine_bit(EXAMPLE_CODES)));
+        assert_eq!(decompress_lzw(&input).unwrap().as_slice(), EXAMPLE_TEXT);
+    }
+
+    #[test]
+    fn decompresses_lzw1_example() {
+        let input = Block::from_vec(pack_msb(&nine_bit(EXAMPLE_CODES)));
+        assert_eq!(decompress_lzw1(&input).unwrap().as_slice(), EXAMPLE_TEXT);
+    }
+
+    #[test]
+    fn handles_code_for_entry_being_defined() {
+        // "aaaa": 'a', then 0x102 ("aa") before it has been fully defined.
+        let input = Block::from_vec(pack_lsb(&nine_bit(&[
+            b'a' as u16,
+            0x102,
+            b'a' as u16,
+            END_CODE,
+        ])));
+        assert_eq!(decompress_lzw(&input).unwrap().as_slice(), b"aaaa");
+    }
+
+    #[test]
+    fn reset_code_clears_dictionary() {
+        let input = Block::from_vec(pack_lsb(&nine_bit(&[
+            b'a' as u16,
+            b'b' as u16,
+            RESET_CODE,
+            b'c' as u16,
+            b'd' as u16,
+            0x102,
+            END_CODE,
+        ])));
+        assert_eq!(decompress_lzw(&input).unwrap().as_slice(), b"abcdcd");
+    }
+
+    #[test]
+    fn widens_codes_as_dictionary_grows() {
+        // Each literal after the first adds an entry. Once the dictionary
+        // reaches 0x200 entries, codes are read with 10 bits.
+        let literals: Vec<u16> = (0..=0xFF).chain(0..=0xFF).collect();
+        let mut codes = Vec::new();
+        let mut next_token = FIRST_TOKEN;
+        let mut width = 9;
+        for (i, &literal) in literals.iter().enumerate() {
+            codes.push((literal, width));
+            if i > 0 {
+                next_token += 1;
+            }
+            if next_token > 0x1FF {
+                width = 10;
+            }
+        }
+        codes.push((END_CODE, width));
+        let expected: Vec<u8> = literals.iter().map(|&literal| literal as u8).collect();
+        let input = Block::from_vec(pack_lsb(&codes));
+        assert_eq!(decompress_lzw(&input).unwrap().as_slice(), expected);
+    }
+
+    #[test]
+    fn rejects_codes_past_dictionary() {
+        let input = Block::from_vec(pack_lsb(&nine_bit(&[b'a' as u16, 0x110, END_CODE])));
+        assert!(decompress_lzw(&input).is_err());
+    }
+}
@@ -102,9 +102,9 @@ impl<R: Read + Seek> DataReader for IoDataReader<R> {
     }
 
     fn read_u24_le(&mut self) -> io::Result<u32> {
-        let mut buf = [0; 4];
-        self.0.read_exact(&mut buf[0..3])?;
-        Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], 0]))
+        let mut buf = [0; 3];
+        self.0.read_exact(&mut buf)?;
+        Ok(crate::numbers::from_le_bytes_u24(buf))
     }
 
     fn read_u32_le(&mut self) -> io::Result<u32> {
@@ -7,6 +7,32 @@ pub trait DataWriter {
     fn write_u16_le(&mut self, value: u16) -> io::Result<()>;
     fn write_u32_le(&mut self, value: u32) -> io::Result<()>;
     fn write_block(&mut self, block: &Block) -> io::Result<()>;
+
+    /// Writes a block of bytes prefixed by its length as a little-endian u16.
+    ///
+    /// Returns an error if the data is too long for its length to fit in a
+    /// u16.
+    fn write_len_prefixed_u16_le(&mut self, data: &[u8]) -> io::Result<()> {
+        let len: u16 = data.len().try_into().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Data of length {} is too long for a u16 prefix", data.len()),
+            )
+        })?;
+        self.write_u16_le(len)?;
+        for &byte in data {
+            self.write_u8(byte)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `count` copies of the `pad` byte.
+    fn write_padding(&mut self, count: usize, pad: u8) -> io::Result<()> {
+        for _ in 0..count {
+            self.write_u8(pad)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct IoDataWriter<W>(W);
@@ -15,6 +41,10 @@ impl<W: io::Write + io::Seek> IoDataWriter<W> {
     pub fn new(writer: W) -> IoDataWriter<W> {
         IoDataWriter(writer)
     }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
 }
 
 impl<W: io::Write + io::Seek> DataWriter for IoDataWriter<W> {
@@ -31,6 +61,57 @@ impl<W: io::Write + io::Seek> DataWriter for IoDataWriter<W> {
     }
 
     fn write_block(&mut self, block: &Block) -> io::Result<()> {
-        self.0.write_all(&block.read_all()?)
+        block.write_to(&mut self.0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written_bytes(body: impl FnOnce(&mut IoDataWriter<io::Cursor<Vec<u8>>>)) -> Vec<u8> {
+        let mut writer = IoDataWriter::new(io::Cursor::new(Vec::new()));
+        body(&mut writer);
+        writer.0.into_inner()
+    }
+
+    #[test]
+    fn write_len_prefixed_u16_le_emits_length_then_data() {
+        let bytes = written_bytes(|writer| {
+            writer
+                .write_len_prefixed_u16_le(&[0xAA, 0xBB, 0xCC])
+                .unwrap();
+        });
+        assert_eq!(bytes, vec![0x03, 0x00, 0xAA, 0xBB, 0xCC]);
+    }
+
+    #[test]
+    fn write_len_prefixed_u16_le_handles_empty_data() {
+        let bytes = written_bytes(|writer| {
+            writer.write_len_prefixed_u16_le(&[]).unwrap();
+        });
+        assert_eq!(bytes, vec![0x00, 0x00]);
+    }
+
+    #[test]
+    fn write_len_prefixed_u16_le_rejects_oversized_data() {
+        let bytes = written_bytes(|writer| {
+            let data = vec![0; u16::MAX as usize + 1];
+            let err = writer.write_len_prefixed_u16_le(&data).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        });
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn write_padding_emits_pad_bytes() {
+        let bytes = written_bytes(|writer| {
+            writer.write_u8(0x01).unwrap();
+            writer.write_padding(3, 0xFF).unwrap();
+            writer.write_padding(0, 0xEE).unwrap();
+            writer.write_u8(0x02).unwrap();
+        });
+        assert_eq!(bytes, vec![0x01, 0xFF, 0xFF, 0xFF, 0x02]);
     }
 }
@@ -1,5 +1,6 @@
 pub mod block;
 pub mod buffer;
+pub mod cancel;
 pub mod compression;
 pub mod data_reader;
 pub mod data_writer;
@@ -83,3 +83,127 @@ pub fn modify_u16_le_in_slice(
     slice.copy_from_slice(&new_value.to_le_bytes());
     Ok(())
 }
+
+/// The largest value that fits in an unsigned 24-bit integer.
+pub const U24_MAX: u32 = 0x00FF_FFFF;
+
+fn check_u24(value: u32) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        value <= U24_MAX,
+        "number {} cannot be safely narrowed to a 24-bit integer",
+        value
+    );
+    Ok(())
+}
+
+/// Packs the value into 3 little-endian bytes.
+///
+/// Returns an error if the value does not fit in 24 bits, rather than
+/// silently dropping the high byte.
+pub fn to_le_bytes_u24(value: u32) -> anyhow::Result<[u8; 3]> {
+    check_u24(value)?;
+    let [b0, b1, b2, _] = value.to_le_bytes();
+    Ok([b0, b1, b2])
+}
+
+/// Packs the value into 3 big-endian bytes.
+///
+/// Returns an error if the value does not fit in 24 bits, rather than
+/// silently dropping the high byte.
+pub fn to_be_bytes_u24(value: u32) -> anyhow::Result<[u8; 3]> {
+    check_u24(value)?;
+    let [_, b0, b1, b2] = value.to_be_bytes();
+    Ok([b0, b1, b2])
+}
+
+pub fn from_le_bytes_u24(bytes: [u8; 3]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+pub fn from_be_bytes_u24(bytes: [u8; 3]) -> u32 {
+    u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]])
+}
+
+/// Returns true if `value` is a multiple of `align`, which must be a power
+/// of two.
+pub fn is_aligned(value: usize, align: usize) -> bool {
+    debug_assert!(align.is_power_of_two(), "{} is not a power of two", align);
+    value & (align - 1) == 0
+}
+
+/// Rounds `value` up to the nearest multiple of `align`, which must be a
+/// power of two.
+pub fn align_up(value: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "{} is not a power of two", align);
+    (value + (align - 1)) & !(align - 1)
+}
+
+/// Rounds `value` down to the nearest multiple of `align`, which must be a
+/// power of two.
+pub fn align_down(value: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "{} is not a power of two", align);
+    value & !(align - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u24_le_round_trip() {
+        assert_eq!(to_le_bytes_u24(0x123456).unwrap(), [0x56, 0x34, 0x12]);
+        assert_eq!(from_le_bytes_u24([0x56, 0x34, 0x12]), 0x123456);
+        assert_eq!(to_le_bytes_u24(0).unwrap(), [0, 0, 0]);
+        assert_eq!(to_le_bytes_u24(U24_MAX).unwrap(), [0xFF, 0xFF, 0xFF]);
+        assert_eq!(from_le_bytes_u24([0xFF, 0xFF, 0xFF]), U24_MAX);
+    }
+
+    #[test]
+    fn u24_be_round_trip() {
+        assert_eq!(to_be_bytes_u24(0x123456).unwrap(), [0x12, 0x34, 0x56]);
+        assert_eq!(from_be_bytes_u24([0x12, 0x34, 0x56]), 0x123456);
+        assert_eq!(to_be_bytes_u24(U24_MAX).unwrap(), [0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn u24_rejects_values_with_high_byte_set() {
+        assert!(to_le_bytes_u24(U24_MAX + 1).is_err());
+        assert!(to_be_bytes_u24(U24_MAX + 1).is_err());
+        assert!(to_le_bytes_u24(0x0100_0000 | 0x56).is_err());
+    }
+
+    #[test]
+    fn align_leaves_aligned_values_unchanged() {
+        for value in [0, 4, 8, 128] {
+            assert!(is_aligned(value, 4));
+            assert_eq!(align_up(value, 4), value);
+            assert_eq!(align_down(value, 4), value);
+        }
+    }
+
+    #[test]
+    fn align_rounds_to_multiples() {
+        assert!(!is_aligned(5, 4));
+        assert_eq!(align_up(5, 4), 8);
+        assert_eq!(align_up(7, 4), 8);
+        assert_eq!(align_down(7, 4), 4);
+        assert_eq!(align_up(1, 128), 128);
+        assert_eq!(align_down(127, 128), 0);
+    }
+
+    #[test]
+    fn align_to_one_is_identity() {
+        for value in [0, 1, 3, 255] {
+            assert!(is_aligned(value, 1));
+            assert_eq!(align_up(value, 1), value);
+            assert_eq!(align_down(value, 1), value);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn align_rejects_non_power_of_two() {
+        align_up(5, 3);
+    }
+}
@@ -11,7 +11,7 @@ use writer::RelocWriter;
 
 use crate::{
     buffer::ToFixedBytes,
-    numbers::bit_convert::NumConvert as _,
+    numbers::{align_up, bit_convert::NumConvert as _},
     symbol::{Symbol, WeakSymbolMap},
 };
 
@@ -241,8 +241,7 @@ impl RelocatableBuffer {
         let alignment = self.alignment.max(other.alignment);
 
         if self.alignment < other.alignment {
-            let padding = other.alignment - (data.len() % other.alignment);
-            data.extend(std::iter::repeat(0).take(padding));
+            data.resize(align_up(data.len(), other.alignment), 0);
         }
 
         // The self section is now aligned, with sufficient padding to
@@ -328,10 +327,8 @@ impl RelocWriter for RelocatableBufferBuilder {
 
         // We still align the section to the requested alignment, even if
         // the section is already aligned to a higher value.
-        let padding = alignment - (self.section.data.len() % alignment);
-        if padding != alignment {
-            self.section.data.extend(std::iter::repeat(0).take(padding));
-        }
+        let aligned_len = align_up(self.section.data.len(), alignment);
+        self.section.data.resize(aligned_len, 0);
     }
 
     fn mark_symbol(&mut self, symbol: Symbol) {
@@ -517,4 +514,22 @@ mod tests {
             .is_err());
         Ok(())
     }
+
+    #[test]
+    fn merge_pads_only_unaligned_sections() -> anyhow::Result<()> {
+        let merged = RelocatableBuffer::from_vec(vec![1, 2, 3, 4], 1)
+            .merge(RelocatableBuffer::from_vec(vec![5], 4))?;
+        assert_eq!(
+            merged.resolve_all(&NullExternalResolver)?,
+            vec![1, 2, 3, 4, 5]
+        );
+
+        let merged = RelocatableBuffer::from_vec(vec![1, 2, 3], 1)
+            .merge(RelocatableBuffer::from_vec(vec![5], 4))?;
+        assert_eq!(
+            merged.resolve_all(&NullExternalResolver)?,
+            vec![1, 2, 3, 0, 5]
+        );
+        Ok(())
+    }
 }
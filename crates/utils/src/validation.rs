@@ -81,6 +81,23 @@ impl ValidationError {
         }
     }
 
+    /// Splits the error into its individual failures, in order. Each failure
+    /// keeps the context it was reported under.
+    pub fn into_failures(self) -> Vec<ValidationError> {
+        match self {
+            ValidationError::Single(_) => vec![self],
+            ValidationError::Multiple(Multiple(errors)) => errors
+                .into_iter()
+                .flat_map(ValidationError::into_failures)
+                .collect(),
+            ValidationError::Context(Context { context, error }) => error
+                .into_failures()
+                .into_iter()
+                .map(|failure| failure.with_context(context.clone()))
+                .collect(),
+        }
+    }
+
     pub fn join(self, other: Self) -> Self {
         match (self, other) {
             (ValidationError::Multiple(mut first), ValidationError::Multiple(second)) => {
@@ -208,11 +225,15 @@ where
 
 pub struct MultiValidator {
     result: Result<(), ValidationError>,
+    warnings: Vec<ValidationError>,
 }
 
 impl MultiValidator {
     pub fn new() -> Self {
-        Self { result: Ok(()) }
+        Self {
+            result: Ok(()),
+            warnings: Vec::new(),
+        }
     }
 
     pub fn with_result<E>(&mut self, item: Result<(), E>) -> &mut Self
@@ -233,6 +254,16 @@ impl MultiValidator {
         self
     }
 
+    /// Records a problem that should be reported, but does not make the
+    /// validation fail.
+    pub fn with_warning<E>(&mut self, item: E) -> &mut Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.warnings.push(ValidationError::from_any(item));
+        self
+    }
+
     pub fn validate_ctxt<F, E>(&mut self, ctxt: impl Into<String>, validator: F) -> &mut Self
     where
         F: FnOnce() -> Result<(), E>,
@@ -246,9 +277,47 @@ impl MultiValidator {
         self
     }
 
+    /// Returns the collected errors, resetting the validator. Warnings are
+    /// discarded; use [`MultiValidator::build_report`] to keep them.
     pub fn build(&mut self) -> Result<(), ValidationError> {
+        self.warnings.clear();
         std::mem::replace(&mut self.result, Ok(()))
     }
+
+    /// Returns the collected errors and warnings, resetting the validator.
+    pub fn build_report(&mut self) -> ValidationReport {
+        let errors = match std::mem::replace(&mut self.result, Ok(())) {
+            Ok(()) => Vec::new(),
+            Err(err) => err.into_failures(),
+        };
+        ValidationReport {
+            errors,
+            warnings: std::mem::take(&mut self.warnings),
+        }
+    }
+}
+
+/// The errors and warnings found by a [`MultiValidator`].
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    /// Returns true if there are neither errors nor warnings.
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+
+    /// Returns the warnings if there were no errors, and otherwise all of the
+    /// errors joined together.
+    pub fn into_result(self) -> Result<Vec<ValidationError>, ValidationError> {
+        match self.errors.into_iter().reduce(ValidationError::join) {
+            Some(err) => Err(err),
+            None => Ok(self.warnings),
+        }
+    }
 }
 
 impl Default for MultiValidator {
@@ -257,6 +326,20 @@ impl Default for MultiValidator {
     }
 }
 
+/// Runs the validator on every item, collecting all of the failures into a
+/// single error. Each failure is given the index of its item as context.
+pub fn validate_all<T, I, F>(items: I, validator: F) -> Result<(), ValidationError>
+where
+    I: IntoIterator<Item = T>,
+    F: Fn(&T) -> Result<(), ValidationError>,
+{
+    let mut multi_validator = MultiValidator::new();
+    for (index, item) in items.into_iter().enumerate() {
+        multi_validator.validate_ctxt(format!("{}", index), || validator(&item));
+    }
+    multi_validator.build()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,6 +355,69 @@ mod tests {
         assert!(matches!(err, ValidationError::Multiple(_)));
     }
 
+    #[test]
+    fn into_failures_flattens_nested_errors() {
+        let err = ValidationError::from("a".to_string())
+            .join(
+                ValidationError::from("b".to_string())
+                    .join("c".to_string().into())
+                    .with_context("inner"),
+            )
+            .with_context("outer");
+        let failures = err
+            .into_failures()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            failures,
+            vec![
+                "outer:\n  a\n",
+                "outer: inner:\n  b\n",
+                "outer: inner:\n  c\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_all_collects_every_failure() {
+        let result = validate_all(vec![1, 2, 3, 4, 5], |value| {
+            if value % 2 == 0 {
+                Err(format!("{} is even", value).into())
+            } else {
+                Ok(())
+            }
+        });
+        let Err(ValidationError::Multiple(Multiple(errors))) = result else {
+            panic!("expected multiple errors");
+        };
+        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(errors, vec!["1:\n  2 is even\n", "3:\n  4 is even\n"]);
+
+        assert!(validate_all(vec![1, 3, 5], |_| Ok(())).is_ok());
+    }
+
+    #[test]
+    fn warnings_do_not_fail_validation() {
+        let mut validator = MultiValidator::new();
+        validator.with_warning(ValidationError::from("only one line".to_string()));
+        let report = validator.build_report();
+        assert!(!report.is_clean());
+        let warnings = report.into_result().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].to_string(), "only one line");
+
+        validator
+            .with_warning(ValidationError::from("only one line".to_string()))
+            .with_err(ValidationError::from("missing line".to_string()));
+        let report = validator.build_report();
+        assert_eq!((report.errors.len(), report.warnings.len()), (1, 1));
+        let err = report.into_result().unwrap_err();
+        assert_eq!(err.to_string(), "missing line");
+
+        assert!(validator.build_report().is_clean());
+    }
+
     #[test]
     fn test_auto_wrap() {
         let err: Box<dyn std::error::Error + Send + Sync> = "test".to_string().into();
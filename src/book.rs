@@ -1,15 +1,33 @@
 //! Code to manage the organization and generation of VO scripts,
 //! (referred to as "books" to disambguate from script resources).
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+};
 
 use builder::ConversationKey;
+use sci_resources::{
+    types::msg::{
+        write_message_resource, MessageEntry, MessageFormatVersion, MessageId, MessageRef,
+    },
+    ResourceId, ResourceType,
+};
 use serde::{Deserialize, Serialize};
 
-use sci_utils::validation::{MultiValidator, ValidationError};
+use sci_utils::{
+    block::Block,
+    data_writer::IoDataWriter,
+    validation::{MultiValidator, ValidationError},
+};
 
 pub mod builder;
 pub mod config;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod json;
+mod merge;
+pub mod text;
 
 // Raw IDs.
 //
@@ -36,6 +54,64 @@ struct RawSequenceId(u8);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 struct RawTalkerId(u8);
 
+/// Adds `succ` and `pred` helpers to raw numeric ID types. These return
+/// `None` rather than overflowing at the ends of the ID's range.
+macro_rules! impl_raw_id_steps {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                /// Returns the ID following this one, if any.
+                #[cfg_attr(not(test), expect(dead_code))]
+                fn succ(self) -> Option<Self> {
+                    self.0.checked_add(1).map(Self)
+                }
+
+                /// Returns the ID preceding this one, if any.
+                #[cfg_attr(not(test), expect(dead_code))]
+                fn pred(self) -> Option<Self> {
+                    self.0.checked_sub(1).map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_id_steps!(RawRoomId, RawNounId, RawVerbId, RawConditionId, RawTalkerId);
+
+impl RawVerbId {
+    /// The verb ID used by conversations that are not triggered by a verb
+    /// (e.g. cutscenes).
+    const NONE: RawVerbId = RawVerbId(0);
+
+    fn is_none(self) -> bool {
+        self == Self::NONE
+    }
+}
+
+impl RawConditionId {
+    /// The condition ID used by conversations that apply regardless of
+    /// condition.
+    const NONE: RawConditionId = RawConditionId(0);
+
+    fn is_none(self) -> bool {
+        self == Self::NONE
+    }
+}
+
+impl RawSequenceId {
+    /// Returns the sequence ID following this one, or `None` if this is the
+    /// last possible sequence ID.
+    fn next(self) -> Option<Self> {
+        self.0.checked_add(1).map(Self)
+    }
+
+    /// Iterates over the sequence IDs from `start` (inclusive) to `end`
+    /// (exclusive).
+    fn range(start: Self, end: Self) -> impl Iterator<Item = Self> {
+        (start.0..end.0).map(Self)
+    }
+}
+
 // Book Specific IDs.
 
 /// An identifier for a role.
@@ -54,6 +130,11 @@ impl RoomId {
     pub fn room_num(&self) -> u16 {
         self.0 .0
     }
+
+    /// Returns the ID string of the room, as accepted by [`Book::resolve`].
+    pub fn to_id_string(self) -> String {
+        format!("room-{}", self.room_num())
+    }
 }
 
 impl std::fmt::Debug for RoomId {
@@ -74,6 +155,12 @@ impl std::fmt::Debug for VerbId {
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RoleId(RawRoleId);
 
+impl RoleId {
+    pub fn as_str(&self) -> &str {
+        &self.0 .0
+    }
+}
+
 impl std::fmt::Debug for RoleId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("RoleId").field(&self.0 .0).finish()
@@ -91,6 +178,11 @@ impl NounId {
     pub fn noun_num(&self) -> u8 {
         self.1 .0
     }
+
+    /// Returns the ID string of the noun, as accepted by [`Book::resolve`].
+    pub fn to_id_string(self) -> String {
+        format!("noun-{}-{}", self.room_num(), self.noun_num())
+    }
 }
 
 impl std::fmt::Debug for NounId {
@@ -118,6 +210,12 @@ impl ConditionId {
     pub fn condition_num(&self) -> u8 {
         self.1 .0
     }
+
+    /// Returns the ID string of the condition, as accepted by
+    /// [`Book::resolve`].
+    pub fn to_id_string(self) -> String {
+        format!("cond-{}-{}", self.0.room_num(), self.condition_num())
+    }
 }
 
 impl std::fmt::Debug for ConditionId {
@@ -142,11 +240,23 @@ impl ConversationId {
     }
 
     pub fn verb_num(&self) -> u8 {
-        self.1.verb().0
+        self.1.verb_num()
     }
 
     pub fn condition_num(&self) -> u8 {
-        self.1.condition().0
+        self.1.condition_num()
+    }
+
+    /// Returns the ID string of the conversation, as accepted by
+    /// [`Book::resolve`].
+    pub fn to_id_string(self) -> String {
+        format!(
+            "conv-{}-{}-{}-{}",
+            self.room_num(),
+            self.noun_num(),
+            self.verb_num(),
+            self.condition_num()
+        )
     }
 }
 
@@ -184,6 +294,18 @@ impl LineId {
     pub fn sequence_num(&self) -> u8 {
         self.1 .0
     }
+
+    /// Returns the ID string of the line, as accepted by [`Book::resolve`].
+    pub fn to_id_string(self) -> String {
+        format!(
+            "line-{}-{}-{}-{}-{}",
+            self.room_num(),
+            self.noun_num(),
+            self.verb_num(),
+            self.condition_num(),
+            self.sequence_num()
+        )
+    }
 }
 
 impl std::fmt::Debug for LineId {
@@ -198,6 +320,86 @@ impl std::fmt::Debug for LineId {
     }
 }
 
+impl LineId {
+    /// Parses a line pattern of the form `line-<room>-<noun>-<verb>-<cond>-<seq>`,
+    /// where any component may be `*` or empty to match any value (e.g.
+    /// `line-110-*-*-*-*` matches every line in room 110).
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn parse_pattern(s: &str) -> Result<LinePattern, IdConversionError> {
+        let err = || IdConversionError(s.to_string());
+        let rest = s.strip_prefix("line-").ok_or_else(err)?;
+        let parts = rest.split('-').collect::<Vec<_>>();
+        let [room, noun, verb, condition, sequence] = parts[..] else {
+            return Err(err());
+        };
+
+        fn component<T: std::str::FromStr>(part: &str) -> Result<Option<T>, ()> {
+            match part {
+                "" | "*" => Ok(None),
+                _ => part.parse().map(Some).map_err(|_| ()),
+            }
+        }
+
+        Ok(LinePattern {
+            room: component(room).map_err(|()| err())?,
+            noun: component(noun).map_err(|()| err())?,
+            verb: component(verb).map_err(|()| err())?,
+            condition: component(condition).map_err(|()| err())?,
+            sequence: component(sequence).map_err(|()| err())?,
+        })
+    }
+}
+
+/// An error produced when a string is not a valid ID or ID pattern.
+#[derive(thiserror::Error, Debug)]
+#[error("Invalid ID string: {0:?}")]
+pub struct IdConversionError(String);
+
+/// A pattern matching a set of lines. Components that are `None` match any
+/// value.
+///
+/// See [`LineId::parse_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinePattern {
+    pub room: Option<u16>,
+    pub noun: Option<u8>,
+    pub verb: Option<u8>,
+    pub condition: Option<u8>,
+    pub sequence: Option<u8>,
+}
+
+impl LinePattern {
+    /// Returns true if every component of the pattern matches `id`.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn matches(&self, id: &LineId) -> bool {
+        fn matches_component<T: PartialEq>(pattern: Option<T>, value: T) -> bool {
+            pattern.is_none_or(|pattern| pattern == value)
+        }
+        matches_component(self.room, id.room_num())
+            && matches_component(self.noun, id.noun_num())
+            && matches_component(self.verb, id.verb_num())
+            && matches_component(self.condition, id.condition_num())
+            && matches_component(self.sequence, id.sequence_num())
+    }
+}
+
+impl std::fmt::Display for LinePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn component<T: std::fmt::Display>(value: Option<T>) -> String {
+            value.map_or_else(|| "*".to_string(), |value| value.to_string())
+        }
+        write!(
+            f,
+            "line-{}-{}-{}-{}-{}",
+            component(self.room),
+            component(self.noun),
+            component(self.verb),
+            component(self.condition),
+            component(self.sequence)
+        )
+    }
+}
+
 // Entries
 //
 // These are the actual data structures that are stored in the book.
@@ -206,42 +408,57 @@ impl std::fmt::Debug for LineId {
 //
 // Public access is provided by the handle types below.
 
+#[derive(Clone)]
 struct ConditionEntry {
     /// If this was configured with a description in the input config file,
     /// this will be Some.
     builder: builder::ConditionEntry,
 }
 
+#[derive(Clone)]
 struct LineEntry {
     text: String,
     talker: RawTalkerId,
+    audio_status: Option<AudioStatus>,
+    /// The reference stored in the line's message record, kept so the
+    /// record can be written back unchanged.
+    reference: MessageRef,
 }
 
+#[derive(Clone)]
 struct ConversationEntry {
     lines: BTreeMap<RawSequenceId, LineEntry>,
+    note: Option<String>,
+    needs_review: bool,
+    play_order: Option<u32>,
 }
 
+#[derive(Clone)]
 struct NounEntry {
     desc: Option<String>,
     is_cutscene: bool,
     conversations: BTreeMap<ConversationKey, ConversationEntry>,
 }
 
+#[derive(Clone)]
 struct RoomEntry {
     name: Option<String>,
     conditions: BTreeMap<RawConditionId, ConditionEntry>,
     nouns: BTreeMap<RawNounId, NounEntry>,
 }
 
+#[derive(Clone, PartialEq, Eq)]
 struct RoleEntry {
     name: String,
     short_name: String,
 }
 
+#[derive(Clone, PartialEq, Eq)]
 struct TalkerEntry {
     role_id: RawRoleId,
 }
 
+#[derive(Clone, PartialEq, Eq)]
 struct VerbEntry {
     name: String,
 }
@@ -267,10 +484,21 @@ impl<'a> Line<'a> {
         LineId(self.parent.id(), self.raw_id)
     }
 
-    pub fn text(&self) -> &str {
+    pub fn text(&self) -> &'a str {
         &self.entry.text
     }
 
+    /// Get the text of the line with all control codes removed.
+    pub fn plain_text(&self) -> String {
+        text::plain_text(self.text())
+    }
+
+    /// Counts the whitespace-separated words in the line, ignoring control
+    /// codes.
+    pub fn word_count(&self) -> usize {
+        text::word_count(self.text())
+    }
+
     pub fn talker(&self) -> Talker<'a> {
         self.book()
             .get_talker(TalkerId(self.entry.talker))
@@ -287,6 +515,25 @@ impl<'a> Line<'a> {
         self.talker().role()
     }
 
+    /// Get the recording status of this line's audio, if it is being
+    /// tracked.
+    pub fn audio_status(&self) -> Option<AudioStatus> {
+        self.entry.audio_status
+    }
+
+    /// Get the raw talker number of this line.
+    pub fn talker_num(&self) -> u8 {
+        self.entry.talker.0
+    }
+
+    /// Get the role of this line, or None if the line's talker is not
+    /// configured in the book.
+    pub fn try_role(&self) -> Option<Role<'a>> {
+        self.book()
+            .get_talker(TalkerId(self.entry.talker))
+            .map(|talker| talker.role())
+    }
+
     #[expect(dead_code)]
     pub fn conversation(&self) -> Conversation<'a> {
         self.parent.clone()
@@ -320,14 +567,44 @@ impl<'a> Conversation<'a> {
         })
     }
 
+    /// Get the number of lines in the conversation.
+    pub fn line_count(&self) -> usize {
+        self.entry.lines.len()
+    }
+
+    /// Get the reviewer note attached to this conversation (if any).
+    pub fn note(&self) -> Option<&str> {
+        self.entry.note.as_deref()
+    }
+
+    /// Returns true if this conversation has been flagged for review.
+    pub fn needs_review(&self) -> bool {
+        self.entry.needs_review
+    }
+
+    /// Get the explicit play order of this conversation within its noun,
+    /// if one was configured.
+    pub fn play_order(&self) -> Option<u32> {
+        self.entry.play_order
+    }
+
     /// Get the noun this conversation is part of.
     pub fn noun(&self) -> Noun<'a> {
         self.parent.clone()
     }
 
+    /// Get the other conversations on the same noun.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn siblings(&self) -> impl Iterator<Item = Conversation<'a>> + 'a {
+        let raw_id = self.raw_id;
+        self.noun()
+            .conversations()
+            .filter(move |conversation| conversation.raw_id != raw_id)
+    }
+
     /// Get the verb used for this conversation (if it exists).
     pub fn verb(&self) -> Option<Verb<'a>> {
-        if self.raw_id.verb() == RawVerbId(0) {
+        if self.raw_id.verb().is_none() {
             return None;
         }
         Some(
@@ -345,7 +622,7 @@ impl<'a> Conversation<'a> {
 
     /// Get the condition needed for this conversation (if it exists).
     pub fn condition(&self) -> Option<Condition<'a>> {
-        if self.raw_id.condition() == RawConditionId(0) {
+        if self.raw_id.condition().is_none() {
             return None;
         }
         Some(
@@ -358,14 +635,22 @@ impl<'a> Conversation<'a> {
 
     pub fn validate_complete(&self) -> Result<(), ValidationError> {
         let mut validator = MultiValidator::new();
-        let mut expected_next = 1;
-        for id in self.entry.lines.keys().map(|id| id.0) {
-            if id != expected_next {
-                validator.with_err(ValidationError::from(
-                    format!("Skipped sequence ID {}, next {}", expected_next, id).to_string(),
-                ));
+        let mut expected_next = Some(RawSequenceId(1));
+        for &id in self.entry.lines.keys() {
+            // Sequence IDs are strictly increasing, so if the previous ID was
+            // the last possible one, this loop would already have ended.
+            let expected = expected_next.expect("Sequence IDs are ordered");
+            if id != expected {
+                let skipped = RawSequenceId::range(expected, id)
+                    .map(|skipped| skipped.0.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                validator.with_err(ValidationError::from(format!(
+                    "Skipped sequence ID(s) {}, next {}",
+                    skipped, id.0
+                )));
             }
-            expected_next = id + 1;
+            expected_next = id.next();
         }
 
         validator.build()
@@ -402,11 +687,19 @@ impl<'a> Condition<'a> {
     }
 
     /// Get the room this condition is part of.
-    #[expect(dead_code)]
     pub fn room(&self) -> Room<'a> {
         self.parent.clone()
     }
 
+    /// Get the other conditions in the same room.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn siblings(&self) -> impl Iterator<Item = Condition<'a>> + 'a {
+        let raw_id = self.raw_id;
+        self.room()
+            .conditions()
+            .filter(move |condition| condition.raw_id != raw_id)
+    }
+
     #[expect(dead_code)]
     fn book(&self) -> &'a Book {
         self.parent.book()
@@ -485,6 +778,15 @@ impl<'a> Noun<'a> {
         self.parent.clone()
     }
 
+    /// Get the other nouns in the same room.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn siblings(&self) -> impl Iterator<Item = Noun<'a>> + 'a {
+        let raw_id = self.raw_id;
+        self.room()
+            .nouns()
+            .filter(move |noun| noun.raw_id != raw_id)
+    }
+
     pub fn conversations(&self) -> impl Iterator<Item = Conversation<'a>> + 'a {
         self.entry.conversations.iter().map({
             let parent = self.clone();
@@ -496,6 +798,22 @@ impl<'a> Noun<'a> {
         })
     }
 
+    /// Returns the conversations in the order they should be played back.
+    ///
+    /// Conversations with an explicit play order come first, sorted by that
+    /// order. The rest follow in key order.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn conversations_in_play_order(&self) -> Vec<Conversation<'a>> {
+        let mut conversations = self.conversations().collect::<Vec<_>>();
+        // The sort is stable, so ties and unordered conversations keep their
+        // key order.
+        conversations.sort_by_key(|conversation| match conversation.play_order() {
+            Some(order) => (0, order),
+            None => (1, 0),
+        });
+        conversations
+    }
+
     fn get_conversation_inner(&self, raw_id: ConversationKey) -> Option<Conversation<'a>> {
         self.entry
             .conversations
@@ -528,6 +846,11 @@ impl<'a> Room<'a> {
         self.entry.name.as_deref().unwrap_or("*NO NAME*")
     }
 
+    /// Get the name of the room, or None if it has not been given one.
+    pub fn try_name(&self) -> Option<&str> {
+        self.entry.name.as_deref()
+    }
+
     /// Get an iterator over all the nouns in this room.
     pub fn nouns(&self) -> impl Iterator<Item = Noun<'a>> + 'a {
         self.entry.nouns.iter().map({
@@ -552,6 +875,45 @@ impl<'a> Room<'a> {
         })
     }
 
+    /// Counts the conversations in this room (across all of its nouns) that
+    /// depend on each condition.
+    ///
+    /// Every condition in the room is listed, including declared conditions
+    /// that no conversation uses. The "no condition" ID is not included.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn condition_usage(&self) -> BTreeMap<ConditionId, ConditionUsage> {
+        let mut usage = self
+            .entry
+            .conditions
+            .iter()
+            .filter(|(raw_id, _)| !raw_id.is_none())
+            .map(|(&raw_id, entry)| {
+                (
+                    ConditionId(self.id(), raw_id),
+                    ConditionUsage {
+                        conversations: 0,
+                        declared: entry.builder.desc().is_some(),
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        for noun in self.entry.nouns.values() {
+            for key in noun.conversations.keys() {
+                if key.condition().is_none() {
+                    continue;
+                }
+                usage
+                    .entry(ConditionId(self.id(), key.condition()))
+                    .or_insert(ConditionUsage {
+                        conversations: 0,
+                        declared: false,
+                    })
+                    .conversations += 1;
+            }
+        }
+        usage
+    }
+
     fn get_condition_inner(&self, raw_id: RawConditionId) -> Option<Condition<'a>> {
         self.entry.conditions.get(&raw_id).map(|entry| Condition {
             parent: self.clone(),
@@ -581,13 +943,11 @@ pub struct Role<'a> {
 }
 
 impl<'a> Role<'a> {
-    #[expect(dead_code)]
     pub fn id(&self) -> RoleId {
         RoleId(self.raw_id.clone())
     }
 
     /// Get the full name of the role.
-    #[expect(dead_code)]
     pub fn name(&self) -> &str {
         &self.entry.name
     }
@@ -597,6 +957,25 @@ impl<'a> Role<'a> {
         &self.entry.short_name
     }
 
+    /// Get an iterator over all lines spoken by this role.
+    ///
+    /// See [`Book::lines_for_role`].
+    pub fn lines(&self) -> impl Iterator<Item = Line<'a>> + 'a {
+        self.parent.lines_for_role(&self.id())
+    }
+
+    /// Get the number of lines spoken by this role.
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Get the total number of words spoken by this role.
+    ///
+    /// See [`Line::word_count`].
+    pub fn word_count(&self) -> usize {
+        self.lines().map(|line| line.word_count()).sum()
+    }
+
     #[expect(dead_code)]
     fn book(&self) -> &Book {
         self.parent
@@ -625,7 +1004,6 @@ impl Book {
         })
     }
 
-    #[expect(dead_code)]
     pub fn roles(&self) -> impl Iterator<Item = Role> {
         self.roles.iter().map(|(raw_id, entry)| Role {
             parent: self,
@@ -660,16 +1038,112 @@ impl Book {
         self.nouns().flat_map(|noun| noun.conversations())
     }
 
+    /// Get an iterator over all conversations that have been flagged for
+    /// review.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn conversations_needing_review(&self) -> impl Iterator<Item = Conversation<'_>> + '_ {
+        self.conversations()
+            .filter(|conversation| conversation.needs_review())
+    }
+
+    /// Compares the lines in the book against a set of lines referenced from
+    /// elsewhere (e.g. found by analyzing script bytecode).
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn coverage_against(&self, referenced: &BTreeSet<LineId>) -> CoverageReport {
+        let book_lines = self.lines().map(|line| line.id()).collect::<BTreeSet<_>>();
+        CoverageReport {
+            missing: referenced.difference(&book_lines).copied().collect(),
+            orphaned: book_lines.difference(referenced).copied().collect(),
+        }
+    }
+
+    /// Groups lines whose plain text is identical, returning only the texts
+    /// shared by more than one line. Blank lines are not reported.
+    ///
+    /// Groups are ordered by text, and lines within a group by ID.
+    pub fn duplicate_texts(&self) -> Vec<(String, Vec<LineId>)> {
+        let mut lines_by_text: BTreeMap<String, Vec<LineId>> = BTreeMap::new();
+        for line in self.lines() {
+            let text = line.plain_text();
+            if text.trim().is_empty() {
+                continue;
+            }
+            lines_by_text.entry(text).or_default().push(line.id());
+        }
+        lines_by_text
+            .into_iter()
+            .filter(|(_, lines)| lines.len() > 1)
+            .collect()
+    }
+
     pub fn lines(&self) -> impl Iterator<Item = Line> + '_ {
         self.conversations()
             .flat_map(|conversation| conversation.lines())
     }
 
+    /// Get the number of lines in the book.
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Get an iterator over all lines with the given audio status. Lines
+    /// whose status is not tracked are never included.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn lines_with_status(&self, status: AudioStatus) -> impl Iterator<Item = Line<'_>> + '_ {
+        self.lines()
+            .filter(move |line| line.audio_status() == Some(status))
+    }
+
+    /// Get an iterator over all lines spoken by the given role, ordered by
+    /// room, noun, conversation, and sequence.
+    pub fn lines_for_role(&self, id: &RoleId) -> impl Iterator<Item = Line<'_>> + '_ {
+        let id = id.clone();
+        self.lines()
+            .filter(move |line| line.try_role().is_some_and(|role| role.id() == id))
+    }
+
+    /// Get an iterator over all conversations in the book, each paired with
+    /// its lines in sequence order.
+    ///
+    /// The conversations are yielded in the same order as `conversations()`.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn lines_grouped_by_conversation(
+        &self,
+    ) -> impl Iterator<Item = (Conversation<'_>, Vec<Line<'_>>)> + '_ {
+        self.conversations().map(|conversation| {
+            let lines = conversation.lines().collect();
+            (conversation, lines)
+        })
+    }
+
     #[expect(dead_code)]
     pub fn conditions(&self) -> impl Iterator<Item = Condition> + '_ {
         self.rooms().flat_map(|room| room.conditions())
     }
 
+    /// Count how often each pair of distinct roles speak in the same
+    /// conversation.
+    ///
+    /// Each conversation contributes at most one to the count of a given
+    /// pair. Pairs are keyed with the lesser role ID first. Lines whose
+    /// talker has no role are ignored.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn role_co_occurrence(&self) -> BTreeMap<(RoleId, RoleId), usize> {
+        let mut counts = BTreeMap::new();
+        for conversation in self.conversations() {
+            let roles = conversation
+                .lines()
+                .filter_map(|line| line.try_role().map(|role| role.id()))
+                .collect::<BTreeSet<_>>();
+            for (i, first) in roles.iter().enumerate() {
+                for second in roles.iter().skip(i + 1) {
+                    *counts.entry((first.clone(), second.clone())).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+
     pub fn get_talker(&self, id: TalkerId) -> Option<Talker> {
         self.talkers.get(&id.0).map(|entry| Talker {
             parent: self,
@@ -702,7 +1176,6 @@ impl Book {
         })
     }
 
-    #[expect(dead_code)]
     pub fn get_condition(&self, id: ConditionId) -> Option<Condition> {
         self.get_room(id.0)
             .and_then(|room| room.get_condition_inner(id.1))
@@ -718,9 +1191,899 @@ impl Book {
             .and_then(|noun| noun.get_conversation_inner(id.1))
     }
 
-    #[expect(dead_code)]
     pub fn get_line(&self, id: LineId) -> Option<Line> {
         self.get_conversation(id.0)
             .and_then(|conversation| conversation.get_line_inner(id.1))
     }
+
+    /// Returns the resources that hold this book's content: the message
+    /// resource of every room, and the audio resources for its lines, as
+    /// named by `audio_naming`.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn required_resources(&self, audio_naming: AudioNaming) -> BTreeSet<ResourceId> {
+        let mut resources = BTreeSet::new();
+        for (room_id, room) in &self.rooms {
+            resources.insert(ResourceId::new(ResourceType::Message, room_id.0));
+            let has_lines = room
+                .nouns
+                .values()
+                .flat_map(|noun| noun.conversations.values())
+                .any(|conversation| !conversation.lines.is_empty());
+            match audio_naming {
+                AudioNaming::None => {}
+                AudioNaming::Audio36 => {
+                    if has_lines {
+                        resources.insert(ResourceId::new(ResourceType::Audio36, room_id.0));
+                    }
+                }
+                AudioNaming::Audio(resource_num) => {
+                    for (noun_id, noun) in &room.nouns {
+                        for (key, conversation) in &noun.conversations {
+                            let conv_id = ConversationId(NounId(RoomId(*room_id), *noun_id), *key);
+                            for sequence in conversation.lines.keys() {
+                                let line_id = LineId(conv_id, *sequence);
+                                resources.insert(ResourceId::new(
+                                    ResourceType::Audio,
+                                    resource_num(line_id),
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        resources
+    }
+
+    /// Serializes the lines of a room back into a message resource.
+    ///
+    /// Talker numbers, sequence numbers, and references to other messages
+    /// are written as they appear in the book.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn to_message_resource(
+        &self,
+        room: RoomId,
+        version: MessageFormatVersion,
+    ) -> io::Result<Block> {
+        let room = self.get_room(room).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("No such room: {:?}", room))
+        })?;
+        // Nouns, conversation keys, and sequences all iterate in the same
+        // order as message IDs, so the lines are already sorted.
+        let lines = room.nouns().flat_map(|noun| {
+            noun.conversations()
+                .flat_map(|conversation| conversation.lines())
+                .collect::<Vec<_>>()
+        });
+        let messages = lines.map(|line| {
+            let id = line.id();
+            MessageEntry {
+                id: MessageId::new(
+                    id.noun_num(),
+                    id.verb_num(),
+                    id.condition_num(),
+                    id.sequence_num(),
+                ),
+                talker: line.talker_num(),
+                text: line.text(),
+                reference: line.entry.reference,
+            }
+        });
+        let mut writer = IoDataWriter::new(io::Cursor::new(Vec::new()));
+        write_message_resource(&mut writer, version, messages)?;
+        Ok(Block::from_vec(writer.into_inner().into_inner()))
+    }
+
+    /// Looks up the entity named by an ID string, as used for section IDs in
+    /// generated documents (e.g. `room-100`, `noun-100-1`, `conv-100-1-2-0`,
+    /// `line-100-1-2-0-1`, or `cond-100-3`).
+    ///
+    /// Returns None if the string is malformed, or the entity does not exist.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn resolve(&self, id_str: &str) -> Option<BookEntity<'_>> {
+        let (prefix, rest) = id_str.split_once('-')?;
+        let nums = rest
+            .split('-')
+            .map(str::parse::<u16>)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        let (room_num, rest) = nums.split_first()?;
+        let room_id = RoomId(RawRoomId(*room_num));
+        let byte = |num: &u16| u8::try_from(*num).ok();
+        match (prefix, rest) {
+            ("room", []) => self.get_room(room_id).map(BookEntity::Room),
+            ("cond", [cond]) => self
+                .get_condition(ConditionId(room_id, RawConditionId(byte(cond)?)))
+                .map(BookEntity::Condition),
+            ("noun", [noun]) => self
+                .get_noun(NounId(room_id, RawNounId(byte(noun)?)))
+                .map(BookEntity::Noun),
+            ("conv", [noun, verb, cond]) => self
+                .get_conversation(ConversationId(
+                    NounId(room_id, RawNounId(byte(noun)?)),
+                    ConversationKey::from_nums(byte(verb)?, byte(cond)?),
+                ))
+                .map(BookEntity::Conversation),
+            ("line", [noun, verb, cond, seq]) => self
+                .get_line(LineId(
+                    ConversationId(
+                        NounId(room_id, RawNounId(byte(noun)?)),
+                        ConversationKey::from_nums(byte(verb)?, byte(cond)?),
+                    ),
+                    RawSequenceId(byte(seq)?),
+                ))
+                .map(BookEntity::Line),
+            _ => None,
+        }
+    }
+}
+
+/// The production status of a line's voice audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioStatus {
+    /// The line has not been recorded.
+    Missing,
+    /// The line has been recorded, but not yet reviewed.
+    Recorded,
+    /// The recording has been reviewed and accepted.
+    Approved,
+}
+
+/// How a game names the audio resources for book lines.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(test), expect(dead_code))]
+pub enum AudioNaming {
+    /// The game has no voice audio.
+    None,
+    /// Line audio is stored per room as audio36 resources, addressed by
+    /// (noun, verb, condition, sequence) through the room's audio map. All
+    /// lines of a room share the room's resource number.
+    Audio36,
+    /// Each line's audio is a separate audio resource, with the resource
+    /// number given by the function.
+    Audio(fn(LineId) -> u16),
+}
+
+/// The result of [`Book::coverage_against`].
+#[derive(Debug)]
+#[cfg_attr(not(test), expect(dead_code))]
+pub struct CoverageReport {
+    /// Lines that are referenced, but are not in the book.
+    pub missing: Vec<LineId>,
+    /// Lines in the book that are not referenced.
+    pub orphaned: Vec<LineId>,
+}
+
+/// How a condition is used within a room. See [`Room::condition_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConditionUsage {
+    /// The number of conversations that depend on the condition.
+    pub conversations: usize,
+    /// Whether the condition was declared in the book config, rather than
+    /// only being used by conversations.
+    pub declared: bool,
+}
+
+/// A handle to any entity in the book that can be named by an ID string.
+///
+/// See [`Book::resolve`].
+pub enum BookEntity<'a> {
+    Room(Room<'a>),
+    Condition(Condition<'a>),
+    Noun(Noun<'a>),
+    Conversation(Conversation<'a>),
+    Line(Line<'a>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(talker: u8, text: &str) -> LineEntry {
+        LineEntry {
+            text: text.to_string(),
+            talker: RawTalkerId(talker),
+            audio_status: None,
+            reference: MessageRef::default(),
+        }
+    }
+
+    fn conversation(lines: Vec<(u8, LineEntry)>) -> ConversationEntry {
+        ConversationEntry {
+            lines: lines
+                .into_iter()
+                .map(|(seq, line)| (RawSequenceId(seq), line))
+                .collect(),
+            note: None,
+            needs_review: false,
+            play_order: None,
+        }
+    }
+
+    /// Builds a small book with two rooms, three roles, and several
+    /// multi-line conversations.
+    fn test_book() -> Book {
+        let roles = [
+            ("ego", "Ego", "E"),
+            ("narrator", "Narrator", "N"),
+            ("cliffy", "Cliffy", "C"),
+        ]
+        .into_iter()
+        .map(|(id, name, short_name)| {
+            (
+                RawRoleId(id.to_string()),
+                RoleEntry {
+                    name: name.to_string(),
+                    short_name: short_name.to_string(),
+                },
+            )
+        })
+        .collect();
+        let talkers = [(0, "ego"), (1, "narrator"), (2, "cliffy")]
+            .into_iter()
+            .map(|(id, role)| {
+                (
+                    RawTalkerId(id),
+                    TalkerEntry {
+                        role_id: RawRoleId(role.to_string()),
+                    },
+                )
+            })
+            .collect();
+        let verbs = [(1, "Look"), (2, "Talk")]
+            .into_iter()
+            .map(|(id, name)| {
+                (
+                    RawVerbId(id),
+                    VerbEntry {
+                        name: name.to_string(),
+                    },
+                )
+            })
+            .collect();
+
+        let room_100 = RoomEntry {
+            name: Some("Bridge".to_string()),
+            conditions: BTreeMap::new(),
+            nouns: [
+                (
+                    RawNounId(1),
+                    NounEntry {
+                        desc: Some("Captain's chair".to_string()),
+                        is_cutscene: false,
+                        conversations: [
+                            (
+                                ConversationKey::from_nums(1, 0),
+                                conversation(vec![(1, line(1, "It's a chair."))]),
+                            ),
+                            (
+                                ConversationKey::from_nums(2, 0),
+                                conversation(vec![
+                                    (1, line(0, "Hello?")),
+                                    (2, line(2, "Nobody's sitting there.")),
+                                    (3, line(0, "Right.")),
+                                ]),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    },
+                ),
+                (
+                    RawNounId(2),
+                    NounEntry {
+                        desc: None,
+                        is_cutscene: false,
+                        conversations: [(
+                            ConversationKey::from_nums(2, 0),
+                            conversation(vec![
+                                (1, line(2, "Need something?")),
+                                (2, line(1, "He did not.")),
+                            ]),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let room_200 = RoomEntry {
+            name: Some("Engine Room".to_string()),
+            conditions: BTreeMap::new(),
+            nouns: [(
+                RawNounId(1),
+                NounEntry {
+                    desc: None,
+                    is_cutscene: false,
+                    conversations: [(
+                        ConversationKey::from_nums(2, 0),
+                        conversation(vec![
+                            (1, line(0, "Is this thing on?")),
+                            (2, line(1, "It is not.")),
+                            (3, line(2, "Says who?")),
+                        ]),
+                    )]
+                    .into_iter()
+                    .collect(),
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        Book {
+            project_name: "Test Project".to_string(),
+            roles,
+            talkers,
+            verbs,
+            rooms: [(RawRoomId(100), room_100), (RawRoomId(200), room_200)]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn lines_grouped_by_conversation_preserves_grouping_and_order() {
+        let book = test_book();
+        let groups = book
+            .lines_grouped_by_conversation()
+            .map(|(conversation, lines)| {
+                let conv_id = conversation.id();
+                for line in &lines {
+                    assert_eq!(line.id().0, conv_id);
+                }
+                (
+                    (
+                        conv_id.room_num(),
+                        conv_id.noun_num(),
+                        conv_id.verb_num(),
+                        conv_id.condition_num(),
+                    ),
+                    lines
+                        .iter()
+                        .map(|line| line.text().to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            groups,
+            vec![
+                ((100, 1, 1, 0), vec!["It's a chair.".to_string()]),
+                (
+                    (100, 1, 2, 0),
+                    vec![
+                        "Hello?".to_string(),
+                        "Nobody's sitting there.".to_string(),
+                        "Right.".to_string(),
+                    ]
+                ),
+                (
+                    (100, 2, 2, 0),
+                    vec!["Need something?".to_string(), "He did not.".to_string()]
+                ),
+                (
+                    (200, 1, 2, 0),
+                    vec![
+                        "Is this thing on?".to_string(),
+                        "It is not.".to_string(),
+                        "Says who?".to_string(),
+                    ]
+                ),
+            ]
+        );
+
+        // The flattened line stream should match the grouped one.
+        let flattened = book.lines().map(|line| line.id()).collect::<Vec<_>>();
+        let grouped = book
+            .lines_grouped_by_conversation()
+            .flat_map(|(_, lines)| lines.into_iter().map(|line| line.id()))
+            .collect::<Vec<_>>();
+        assert_eq!(flattened, grouped);
+    }
+
+    #[test]
+    fn lines_for_role_visits_lines_in_book_order() {
+        let book = test_book();
+        let role = RoleId(RawRoleId("cliffy".to_string()));
+        let texts = book
+            .lines_for_role(&role)
+            .map(|line| line.text())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec!["Nobody's sitting there.", "Need something?", "Says who?"]
+        );
+
+        let role = book.get_role(&role).unwrap();
+        assert_eq!(
+            role.lines().map(|line| line.id()).collect::<Vec<_>>(),
+            book.lines_for_role(&role.id())
+                .map(|line| line.id())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn lines_for_role_skips_unmapped_talkers() {
+        let mut book = test_book();
+        book.talkers.remove(&RawTalkerId(2));
+        let role = RoleId(RawRoleId("cliffy".to_string()));
+        assert_eq!(book.lines_for_role(&role).count(), 0);
+
+        let role = RoleId(RawRoleId("narrator".to_string()));
+        assert_eq!(book.lines_for_role(&role).count(), 3);
+    }
+
+    #[test]
+    fn counts_lines_and_words() {
+        let book = test_book();
+        assert_eq!(book.line_count(), 9);
+
+        let Some(BookEntity::Conversation(conversation)) = book.resolve("conv-100-1-2-0") else {
+            panic!("expected a conversation");
+        };
+        assert_eq!(conversation.line_count(), 3);
+
+        let Some(BookEntity::Line(line)) = book.resolve("line-100-1-2-0-2") else {
+            panic!("expected a line");
+        };
+        assert_eq!(line.word_count(), 3);
+
+        let cliffy = book
+            .get_role(&RoleId(RawRoleId("cliffy".to_string())))
+            .unwrap();
+        assert_eq!(cliffy.line_count(), 3);
+        assert_eq!(cliffy.word_count(), 7);
+    }
+
+    #[test]
+    fn word_count_skips_control_codes() {
+        let mut book = test_book();
+        book.rooms
+            .get_mut(&RawRoomId(200))
+            .unwrap()
+            .nouns
+            .get_mut(&RawNounId(1))
+            .unwrap()
+            .conversations
+            .get_mut(&ConversationKey::from_nums(2, 0))
+            .unwrap()
+            .lines
+            .get_mut(&RawSequenceId(1))
+            .unwrap()
+            .text = "|f5|Is |c1|this|c| thing on?|f|".to_string();
+
+        let Some(BookEntity::Line(line)) = book.resolve("line-200-1-2-0-1") else {
+            panic!("expected a line");
+        };
+        assert_eq!(line.plain_text(), "Is this thing on?");
+        assert_eq!(line.word_count(), 4);
+    }
+
+    #[test]
+    fn required_resources_covers_messages_and_audio() {
+        let mut book = test_book();
+        book.rooms.insert(
+            RawRoomId(300),
+            RoomEntry {
+                name: Some("Empty Room".to_string()),
+                conditions: BTreeMap::new(),
+                nouns: BTreeMap::new(),
+            },
+        );
+
+        let message = |room| ResourceId::new(ResourceType::Message, room);
+        let audio = |room| ResourceId::new(ResourceType::Audio36, room);
+        assert_eq!(
+            book.required_resources(AudioNaming::None),
+            [message(100), message(200), message(300)]
+                .into_iter()
+                .collect::<BTreeSet<_>>()
+        );
+        assert_eq!(
+            book.required_resources(AudioNaming::Audio36),
+            [
+                message(100),
+                message(200),
+                message(300),
+                audio(100),
+                audio(200)
+            ]
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+        );
+    }
+
+    #[test]
+    fn required_resources_names_audio_per_line() {
+        let book = test_book();
+        let resource_num = |id: LineId| {
+            id.room_num() * 100
+                + u16::from(id.noun_num()) * 20
+                + u16::from(id.verb_num()) * 5
+                + u16::from(id.sequence_num())
+        };
+
+        let resources = book.required_resources(AudioNaming::Audio(resource_num));
+        let audio = resources
+            .iter()
+            .filter(|id| id.type_id() == ResourceType::Audio)
+            .map(|id| id.resource_num())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            audio,
+            vec![10026, 10031, 10032, 10033, 10051, 10052, 20031, 20032, 20033]
+        );
+        assert_eq!(resources.len(), audio.len() + 2);
+    }
+
+    #[test]
+    fn role_co_occurrence_counts_pairs_per_conversation() {
+        let book = test_book();
+        let role = |id: &str| RoleId(RawRoleId(id.to_string()));
+        let counts = book.role_co_occurrence();
+
+        // The single-role conversation in room 100 contributes nothing, and
+        // repeated lines from the same pair in a conversation count once.
+        assert_eq!(
+            counts,
+            [
+                ((role("cliffy"), role("ego")), 2),
+                ((role("cliffy"), role("narrator")), 2),
+                ((role("ego"), role("narrator")), 1),
+            ]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+        );
+        for (first, second) in counts.keys() {
+            assert!(first < second);
+        }
+    }
+
+    #[test]
+    fn role_co_occurrence_skips_unmapped_talkers() {
+        let mut book = test_book();
+        book.talkers.remove(&RawTalkerId(1));
+        let role = |id: &str| RoleId(RawRoleId(id.to_string()));
+
+        // Only the conversations with both ego and cliffy are left.
+        assert_eq!(
+            book.role_co_occurrence(),
+            [((role("cliffy"), role("ego")), 2)]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+        );
+    }
+
+    fn line_id(room: u16, noun: u8, verb: u8, condition: u8, sequence: u8) -> LineId {
+        LineId(
+            ConversationId(
+                NounId(RoomId(RawRoomId(room)), RawNounId(noun)),
+                ConversationKey::from_nums(verb, condition),
+            ),
+            RawSequenceId(sequence),
+        )
+    }
+
+    #[test]
+    fn coverage_reports_missing_and_orphaned_lines() {
+        let book = test_book();
+        let mut referenced = book.lines().map(|line| line.id()).collect::<BTreeSet<_>>();
+        let missing = line_id(100, 1, 2, 0, 4);
+        let orphaned = line_id(200, 1, 2, 0, 3);
+        referenced.insert(missing);
+        assert!(referenced.remove(&orphaned));
+
+        let report = book.coverage_against(&referenced);
+        assert_eq!(report.missing, vec![missing]);
+        assert_eq!(report.orphaned, vec![orphaned]);
+    }
+
+    #[test]
+    fn siblings_exclude_self() {
+        let mut builder = builder::BookBuilder::new(config::BookConfig::default()).unwrap();
+        for (noun, verb, condition) in [(1, 2, 1), (1, 1, 0), (2, 2, 2), (3, 2, 3)] {
+            let conv = ConversationId(
+                NounId(RoomId(RawRoomId(100)), RawNounId(noun)),
+                ConversationKey::from_nums(verb, condition),
+            );
+            builder.add_line(conv, "Line", 0).unwrap();
+        }
+        let book = builder.build().unwrap();
+
+        let noun = book
+            .get_noun(NounId(RoomId(RawRoomId(100)), RawNounId(2)))
+            .unwrap();
+        let noun_siblings = noun
+            .siblings()
+            .map(|noun| noun.id().noun_num())
+            .collect::<Vec<_>>();
+        assert_eq!(noun_siblings, vec![1, 3]);
+
+        let conversation = book
+            .get_conversation(ConversationId(
+                NounId(RoomId(RawRoomId(100)), RawNounId(1)),
+                ConversationKey::from_nums(2, 1),
+            ))
+            .unwrap();
+        let conversation_siblings = conversation
+            .siblings()
+            .map(|conversation| conversation.id().verb_num())
+            .collect::<Vec<_>>();
+        assert_eq!(conversation_siblings, vec![1]);
+
+        let condition = book
+            .get_condition(ConditionId(RoomId(RawRoomId(100)), RawConditionId(2)))
+            .unwrap();
+        let condition_siblings = condition
+            .siblings()
+            .map(|condition| condition.id().condition_num())
+            .collect::<Vec<_>>();
+        assert_eq!(condition_siblings, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn raw_id_steps_stop_at_range_ends() {
+        assert_eq!(RawSequenceId(254).next(), Some(RawSequenceId(255)));
+        assert_eq!(RawSequenceId(255).next(), None);
+        assert_eq!(RawNounId(0).pred(), None);
+        assert_eq!(RawNounId(255).succ(), None);
+        assert_eq!(RawRoomId(u16::MAX).succ(), None);
+        assert_eq!(RawRoomId(0).pred(), None);
+        assert_eq!(RawNounId(1).succ(), Some(RawNounId(2)));
+        assert_eq!(RawVerbId(255).succ(), None);
+        assert_eq!(RawVerbId(1).pred(), Some(RawVerbId(0)));
+        assert_eq!(RawConditionId(3).succ(), Some(RawConditionId(4)));
+        assert_eq!(RawConditionId(3).pred(), Some(RawConditionId(2)));
+        assert_eq!(RawTalkerId(9).succ(), Some(RawTalkerId(10)));
+        assert_eq!(RawTalkerId(0).pred(), None);
+    }
+
+    #[test]
+    fn sentinel_ids_are_zero() {
+        assert_eq!(RawVerbId::NONE, RawVerbId(0));
+        assert_eq!(RawConditionId::NONE, RawConditionId(0));
+        assert!(RawVerbId(0).is_none());
+        assert!(!RawVerbId(1).is_none());
+        assert!(RawConditionId(0).is_none());
+        assert!(!RawConditionId(1).is_none());
+    }
+
+    #[test]
+    fn sequence_range_excludes_end() {
+        assert_eq!(
+            RawSequenceId::range(RawSequenceId(2), RawSequenceId(5)).collect::<Vec<_>>(),
+            vec![RawSequenceId(2), RawSequenceId(3), RawSequenceId(4)]
+        );
+        assert_eq!(
+            RawSequenceId::range(RawSequenceId(5), RawSequenceId(5)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn validate_complete_reports_gaps_up_to_last_sequence() {
+        let mut book = test_book();
+        let conv = book
+            .rooms
+            .get_mut(&RawRoomId(200))
+            .unwrap()
+            .nouns
+            .get_mut(&RawNounId(1))
+            .unwrap()
+            .conversations
+            .get_mut(&ConversationKey::from_nums(2, 0))
+            .unwrap();
+        *conv = conversation(vec![
+            (1, line(0, "One.")),
+            (4, line(0, "Four.")),
+            (255, line(0, "The end.")),
+        ]);
+
+        let Some(BookEntity::Conversation(conversation)) = book.resolve("conv-200-1-2-0") else {
+            panic!("expected a conversation");
+        };
+        let err = conversation.validate_complete().unwrap_err().to_string();
+        assert!(
+            err.contains("Skipped sequence ID(s) 2, 3, next 4"),
+            "{}",
+            err
+        );
+        assert!(err.contains("next 255"), "{}", err);
+    }
+
+    #[test]
+    fn line_pattern_matches_wildcard_components() {
+        let pattern = LineId::parse_pattern("line-110-*-*-*-*").unwrap();
+        assert_eq!(pattern.room, Some(110));
+        assert!(pattern.matches(&line_id(110, 1, 2, 0, 1)));
+        assert!(pattern.matches(&line_id(110, 7, 1, 3, 4)));
+        assert!(!pattern.matches(&line_id(111, 1, 2, 0, 1)));
+
+        let pattern = LineId::parse_pattern("line-110-1--0-*").unwrap();
+        assert!(pattern.matches(&line_id(110, 1, 5, 0, 9)));
+        assert!(!pattern.matches(&line_id(110, 1, 5, 1, 9)));
+    }
+
+    #[test]
+    fn line_pattern_round_trips_through_display() {
+        for pattern_str in ["line-110-*-*-*-*", "line-1-2-3-4-5", "line-*-*-2-*-1"] {
+            let pattern = LineId::parse_pattern(pattern_str).unwrap();
+            assert_eq!(pattern.to_string(), pattern_str);
+            assert_eq!(
+                LineId::parse_pattern(&pattern.to_string()).unwrap(),
+                pattern
+            );
+        }
+        // Empty components are normalized to `*`.
+        assert_eq!(
+            LineId::parse_pattern("line-110----").unwrap().to_string(),
+            "line-110-*-*-*-*"
+        );
+    }
+
+    #[test]
+    fn line_pattern_rejects_malformed_strings() {
+        for pattern_str in [
+            "line-110-*-*-*",
+            "line-110-*-*-*-*-*",
+            "conv-110-*-*-*-*",
+            "line-110-x-*-*-*",
+            "line-110-256-*-*-*",
+        ] {
+            assert!(
+                LineId::parse_pattern(pattern_str).is_err(),
+                "{}",
+                pattern_str
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_finds_each_kind_of_entity() {
+        let book = test_book();
+
+        let Some(BookEntity::Room(room)) = book.resolve("room-200") else {
+            panic!("expected a room");
+        };
+        assert_eq!(room.name(), "Engine Room");
+
+        let Some(BookEntity::Noun(noun)) = book.resolve("noun-100-1") else {
+            panic!("expected a noun");
+        };
+        assert_eq!(noun.desc(), Some("Captain's chair"));
+
+        let Some(BookEntity::Conversation(conversation)) = book.resolve("conv-100-1-2-0") else {
+            panic!("expected a conversation");
+        };
+        assert_eq!(conversation.lines().count(), 3);
+
+        let Some(BookEntity::Line(line)) = book.resolve("line-100-1-2-0-2") else {
+            panic!("expected a line");
+        };
+        assert_eq!(line.text(), "Nobody's sitting there.");
+    }
+
+    #[test]
+    fn resolve_returns_none_for_unknown_ids() {
+        let book = test_book();
+        assert!(book.resolve("room-300").is_none());
+        assert!(book.resolve("line-100-1-2-0-9").is_none());
+        assert!(book.resolve("cond-100-1").is_none());
+        assert!(book.resolve("noun-100-1-2").is_none());
+        assert!(book.resolve("noun-100-300").is_none());
+        assert!(book.resolve("chapter-100").is_none());
+        assert!(book.resolve("room").is_none());
+    }
+
+    #[test]
+    fn conversations_needing_review_returns_only_flagged() {
+        let mut book = test_book();
+        assert_eq!(book.conversations_needing_review().count(), 0);
+
+        let entry = book
+            .rooms
+            .get_mut(&RawRoomId(200))
+            .unwrap()
+            .nouns
+            .get_mut(&RawNounId(1))
+            .unwrap()
+            .conversations
+            .get_mut(&ConversationKey::from_nums(2, 0))
+            .unwrap();
+        entry.needs_review = true;
+        entry.note = Some("Check the delivery.".to_string());
+
+        let flagged = book.conversations_needing_review().collect::<Vec<_>>();
+        assert_eq!(flagged.len(), 1);
+        let id = flagged[0].id();
+        assert_eq!((id.room_num(), id.noun_num(), id.verb_num()), (200, 1, 2));
+        assert_eq!(flagged[0].note(), Some("Check the delivery."));
+    }
+
+    #[test]
+    fn condition_usage_counts_conversations() {
+        let config: config::BookConfig = serde_yml::from_str(
+            r#"
+project_name: Test
+roles: {}
+talkers: []
+verbs: []
+rooms:
+  - id: 100
+    name: Bridge
+    conditions:
+      - id: 1
+        desc: Alarm is on
+      - id: 2
+        desc: Alarm is off
+"#,
+        )
+        .unwrap();
+        let mut builder = builder::BookBuilder::new(config).unwrap();
+        for (noun, verb, condition) in [(1, 2, 1), (2, 2, 1), (2, 1, 0), (3, 2, 3)] {
+            let conv = ConversationId(
+                NounId(RoomId(RawRoomId(100)), RawNounId(noun)),
+                ConversationKey::from_nums(verb, condition),
+            );
+            builder.add_line(conv, "Line", 0).unwrap();
+        }
+        let book = builder.build().unwrap();
+
+        let usage = book
+            .get_room(RoomId(RawRoomId(100)))
+            .unwrap()
+            .condition_usage()
+            .into_iter()
+            .map(|(id, usage)| (id.condition_num(), usage))
+            .collect::<Vec<_>>();
+        let usage_of = |conversations, declared| ConditionUsage {
+            conversations,
+            declared,
+        };
+        assert_eq!(
+            usage,
+            vec![
+                (1, usage_of(2, true)),
+                (2, usage_of(0, true)),
+                (3, usage_of(1, false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_texts_groups_matching_plain_text() {
+        let mut builder = builder::BookBuilder::new(config::BookConfig::default()).unwrap();
+        for (noun, text) in [
+            (1, "Hello there."),
+            (2, "|c|Hello there.|c|"),
+            (3, "Go away."),
+            (4, "Who's there?"),
+        ] {
+            let conv = ConversationId(
+                NounId(RoomId(RawRoomId(100)), RawNounId(noun)),
+                ConversationKey::from_nums(2, 0),
+            );
+            builder.add_line(conv, text, 0).unwrap();
+        }
+        let book = builder.build().unwrap();
+
+        let duplicates = book
+            .duplicate_texts()
+            .into_iter()
+            .map(|(text, lines)| {
+                (
+                    text,
+                    lines.iter().map(|id| id.noun_num()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(duplicates, vec![("Hello there.".to_string(), vec![1, 2])]);
+    }
 }
@@ -2,12 +2,13 @@ use std::collections::{btree_map, BTreeMap};
 
 use itertools::Itertools;
 
-use sci_resources::types::msg::{MessageId, MessageRecord};
+use sci_resources::types::msg::{MessageId, MessageRecord, MessageRef};
 use sci_utils::validation::{IteratorExt as _, MultiValidator, ValidationError};
 
 use super::{
     config::{self, BookConfig},
-    Book, RawConditionId, RawNounId, RawRoleId, RawRoomId, RawSequenceId, RawTalkerId, RawVerbId,
+    AudioStatus, Book, ConversationId, LineId, NounId, RawConditionId, RawNounId, RawRoleId,
+    RawRoomId, RawSequenceId, RawTalkerId, RawVerbId, RoomId,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -86,12 +87,16 @@ where
 pub(super) struct MessageEntry {
     talker: RawTalkerId,
     text: String,
+    audio_status: Option<AudioStatus>,
+    reference: MessageRef,
 }
 impl MessageEntry {
     fn build(&self, _ctxt: &Conversation) -> Result<super::LineEntry, BuildError> {
         Ok(super::LineEntry {
             text: self.text.clone(),
             talker: self.talker,
+            audio_status: self.audio_status,
+            reference: self.reference,
         })
     }
 }
@@ -104,11 +109,23 @@ pub struct ConversationKey {
 }
 
 impl ConversationKey {
-    #[expect(dead_code)]
     pub(super) fn new(verb: RawVerbId, condition: RawConditionId) -> Self {
         Self { verb, condition }
     }
 
+    /// Create a key from plain verb and condition numbers.
+    pub fn from_nums(verb: u8, condition: u8) -> Self {
+        Self::new(RawVerbId(verb), RawConditionId(condition))
+    }
+
+    pub fn verb_num(&self) -> u8 {
+        self.verb.0
+    }
+
+    pub fn condition_num(&self) -> u8 {
+        self.condition.0
+    }
+
     pub(super) fn verb(&self) -> RawVerbId {
         self.verb
     }
@@ -119,19 +136,40 @@ impl ConversationKey {
 }
 
 #[derive(Debug, Clone)]
-pub(super) struct Conversation(BTreeMap<RawSequenceId, MessageEntry>);
+pub(super) struct Conversation {
+    lines: BTreeMap<RawSequenceId, MessageEntry>,
+    note: Option<String>,
+    needs_review: bool,
+    play_order: Option<u32>,
+}
 
 impl Conversation {
     pub fn new() -> Self {
-        Self(BTreeMap::new())
+        Self {
+            lines: BTreeMap::new(),
+            note: None,
+            needs_review: false,
+            play_order: None,
+        }
+    }
+
+    fn with_config(conversation: config::ConversationEntry) -> Self {
+        Self {
+            lines: BTreeMap::new(),
+            note: conversation.note,
+            needs_review: conversation.needs_review,
+            play_order: conversation.play_order,
+        }
     }
 
     pub fn add_message(&mut self, message: &MessageId, record: &MessageRecord) -> BuildResult<()> {
-        match self.0.entry(RawSequenceId(message.sequence())) {
+        match self.lines.entry(RawSequenceId(message.sequence())) {
             btree_map::Entry::Vacant(vac) => {
                 vac.insert(MessageEntry {
                     talker: RawTalkerId(record.talker()),
                     text: record.text().to_string(),
+                    audio_status: None,
+                    reference: record.reference(),
                 });
                 Ok(())
             }
@@ -139,9 +177,38 @@ impl Conversation {
         }
     }
 
+    /// Returns the sequence ID following the last line in the conversation.
+    ///
+    /// Sequence IDs start at 1.
+    fn next_sequence_id(&self) -> BuildResult<RawSequenceId> {
+        match self.lines.last_key_value() {
+            None => Ok(RawSequenceId(1)),
+            Some((last, _)) => last
+                .next()
+                .ok_or_else(|| "Conversation has no free sequence IDs".to_string().into()),
+        }
+    }
+
+    fn add_line(&mut self, talker: RawTalkerId, text: String) -> BuildResult<RawSequenceId> {
+        let sequence = self.next_sequence_id()?;
+        self.lines.insert(
+            sequence,
+            MessageEntry {
+                talker,
+                text,
+                audio_status: None,
+                reference: MessageRef::default(),
+            },
+        );
+        Ok(sequence)
+    }
+
     fn build(&self, _ctxt: &BookBuilder) -> BuildResult<super::ConversationEntry> {
         Ok(super::ConversationEntry {
-            lines: map_values(&self.0, |v| v.build(self))?,
+            lines: map_values(&self.lines, |v| v.build(self))?,
+            note: self.note.clone(),
+            needs_review: self.needs_review,
+            play_order: self.play_order,
         })
     }
 }
@@ -234,20 +301,24 @@ pub(super) struct NounEntry {
 }
 
 impl NounEntry {
-    pub fn with_config(noun_entry: config::NounEntry) -> Self {
-        Self {
+    pub fn with_config(noun_entry: config::NounEntry) -> BuildResult<Self> {
+        Ok(Self {
             desc: Some(noun_entry.desc),
             is_cutscene: noun_entry.is_cutscene,
-            conversation_set: BTreeMap::new(),
+            conversation_set: group_pairs(noun_entry.conversations.into_iter().map(
+                |conversation| {
+                    (
+                        ConversationKey::new(conversation.verb, conversation.condition),
+                        Conversation::with_config(conversation),
+                    )
+                },
+            ))?,
             hidden: noun_entry.hidden,
-        }
+        })
     }
 
     fn add_message(&mut self, message: &MessageId, record: &MessageRecord) -> BuildResult<()> {
-        let key = ConversationKey {
-            verb: RawVerbId(message.verb()),
-            condition: RawConditionId(message.condition()),
-        };
+        let key = ConversationKey::from_nums(message.verb(), message.condition());
 
         self.conversation_set
             .entry(key)
@@ -255,12 +326,32 @@ impl NounEntry {
             .add_message(message, record)
     }
 
+    fn add_line(
+        &mut self,
+        key: ConversationKey,
+        talker: RawTalkerId,
+        text: String,
+    ) -> BuildResult<RawSequenceId> {
+        self.conversation_set
+            .entry(key)
+            .or_insert_with(Conversation::new)
+            .add_line(talker, text)
+    }
+
+    /// Returns the conversations that have at least one line. Conversations
+    /// that are only configured are left out of the book.
+    fn conversations_with_lines(&self) -> impl Iterator<Item = (&ConversationKey, &Conversation)> {
+        self.conversation_set
+            .iter()
+            .filter(|(_, conversation)| !conversation.lines.is_empty())
+    }
+
     fn validate(&self, _ctxt: &BookBuilder) -> ValidateResult {
         let mut validator = MultiValidator::new();
         if self.is_cutscene {
-            match self.conversation_set.iter().exactly_one() {
+            match self.conversations_with_lines().exactly_one() {
                 Ok((key, _)) => {
-                    if key.verb != RawVerbId(0) || key.condition != RawConditionId(0) {
+                    if !key.verb.is_none() || !key.condition.is_none() {
                         validator.with_err(ValidationError::from(format!(
                             "Cutscene noun must have exactly one conversation with verb 0 and condition 0. Found: {:?}",
                             key
@@ -283,7 +374,10 @@ impl NounEntry {
         Ok(super::NounEntry {
             desc: self.desc.clone(),
             is_cutscene: self.is_cutscene,
-            conversations: map_values(&self.conversation_set, |v| v.build(ctxt))?,
+            conversations: self
+                .conversations_with_lines()
+                .map(|(key, conversation)| Ok((*key, conversation.build(ctxt)?)))
+                .collect::<BuildResult<_>>()?,
         })
     }
 }
@@ -338,11 +432,11 @@ impl RoomEntry {
                     },
                 )
             }))?,
-            nouns: group_pairs(
+            nouns: group_pairs_with_errors(
                 room_config
                     .nouns
                     .into_iter()
-                    .map(|noun| (noun.id, NounEntry::with_config(noun))),
+                    .map(|noun| Ok((noun.id, NounEntry::with_config(noun)?))),
             )?,
             hidden: room_config.hidden,
         })
@@ -358,6 +452,22 @@ impl RoomEntry {
             .or_default()
             .add_message(message, record)
     }
+
+    fn add_line(
+        &mut self,
+        noun: RawNounId,
+        key: ConversationKey,
+        talker: RawTalkerId,
+        text: String,
+    ) -> BuildResult<RawSequenceId> {
+        if let btree_map::Entry::Vacant(vac) = self.conditions.entry(key.condition()) {
+            vac.insert(ConditionEntry { desc: None });
+        }
+        self.nouns
+            .entry(noun)
+            .or_default()
+            .add_line(key, talker, text)
+    }
 }
 
 pub struct BookBuilder {
@@ -366,6 +476,7 @@ pub struct BookBuilder {
     talkers: BTreeMap<RawTalkerId, TalkerEntry>,
     verbs: BTreeMap<RawVerbId, VerbEntry>,
     rooms: BTreeMap<RawRoomId, RoomEntry>,
+    allow_duplicate_short_names: bool,
 }
 
 impl BookBuilder {
@@ -399,6 +510,7 @@ impl BookBuilder {
                     .into_iter()
                     .map(|room| Ok((room.id, RoomEntry::from_config(room)?))),
             )?,
+            allow_duplicate_short_names: config.allow_duplicate_short_names,
         };
 
         Ok(builder)
@@ -417,6 +529,99 @@ impl BookBuilder {
         Ok(self)
     }
 
+    /// Append a line to the end of a conversation, creating the conversation
+    /// if needed.
+    ///
+    /// The line is given the next free sequence ID in the conversation, and
+    /// its ID is returned. Fails if the conversation already uses sequence
+    /// ID 255.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn add_line(
+        &mut self,
+        conv: ConversationId,
+        text: impl Into<String>,
+        talker: u8,
+    ) -> BuildResult<LineId> {
+        let sequence = self.rooms.entry(conv.0 .0 .0).or_default().add_line(
+            conv.0 .1,
+            conv.1,
+            RawTalkerId(talker),
+            text.into(),
+        )?;
+        Ok(LineId(conv, sequence))
+    }
+
+    /// Returns the ID and text of every line held by the builder, in ID
+    /// order.
+    pub fn lines(&self) -> impl Iterator<Item = (LineId, &str)> + '_ {
+        self.rooms.iter().flat_map(|(&room_id, room)| {
+            room.nouns.iter().flat_map(move |(&noun_id, noun)| {
+                noun.conversation_set
+                    .iter()
+                    .flat_map(move |(&key, conversation)| {
+                        let conv_id = ConversationId(NounId(RoomId(room_id), noun_id), key);
+                        conversation.lines.iter().map(move |(&sequence, line)| {
+                            (LineId(conv_id, sequence), line.text.as_str())
+                        })
+                    })
+            })
+        })
+    }
+
+    /// Sets the audio status of an existing line. Fails if the line is not
+    /// in the book.
+    pub fn set_audio_status(
+        &mut self,
+        line: LineId,
+        status: AudioStatus,
+    ) -> BuildResult<&mut Self> {
+        let LineId(ConversationId(NounId(RoomId(room_id), noun_id), key), sequence) = line;
+        let entry = self
+            .rooms
+            .get_mut(&room_id)
+            .and_then(|room| room.nouns.get_mut(&noun_id))
+            .and_then(|noun| noun.conversation_set.get_mut(&key))
+            .and_then(|conversation| conversation.lines.get_mut(&sequence))
+            .ok_or_else(|| BuildError::from(format!("No such line: {:?}", line)))?;
+        entry.audio_status = Some(status);
+        Ok(self)
+    }
+
+    /// Applies `f` to the text of every line in the book, in ID order.
+    ///
+    /// Intended for bulk cleanups, such as normalizing whitespace.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn transform_lines<F>(&mut self, mut f: F) -> &mut Self
+    where
+        F: FnMut(LineId, &mut String),
+    {
+        for (&room_id, room) in &mut self.rooms {
+            for (&noun_id, noun) in &mut room.nouns {
+                for (&key, conversation) in &mut noun.conversation_set {
+                    let conv_id = ConversationId(NounId(RoomId(room_id), noun_id), key);
+                    for (&sequence, line) in &mut conversation.lines {
+                        f(LineId(conv_id, sequence), &mut line.text);
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Runs every consistency check that [`BookBuilder::build`] would,
+    /// without constructing the book.
+    ///
+    /// Unlike `build`, each problem found is reported as a separate error.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn validate_only(&self) -> Result<(), Vec<BuildError>> {
+        self.validate().map_err(|err| {
+            err.into_failures()
+                .into_iter()
+                .map(BuildError::from)
+                .collect()
+        })
+    }
+
     pub fn build(self) -> BuildResult<Book> {
         self.validate()?;
         Ok(Book {
@@ -442,6 +647,7 @@ impl BookBuilder {
             .validate_ctxt("roles", || {
                 self.roles.iter().validate_all_values(|e| e.validate(self))
             })
+            .validate_ctxt("role short names", || self.validate_short_names())
             .validate_ctxt("talkers", || {
                 self.talkers
                     .iter()
@@ -457,7 +663,331 @@ impl BookBuilder {
         Ok(())
     }
 
+    fn validate_short_names(&self) -> ValidateResult {
+        if self.allow_duplicate_short_names {
+            return Ok(());
+        }
+        let roles_by_short_name = self
+            .roles
+            .iter()
+            .map(|(role_id, role)| (role.short_name.as_str(), role_id))
+            .into_group_map();
+        let mut validator = MultiValidator::new();
+        for (short_name, role_ids) in roles_by_short_name.into_iter().sorted() {
+            if role_ids.len() > 1 {
+                validator.with_err(ValidationError::from(format!(
+                    "Short name {:?} is shared by roles: {}",
+                    short_name,
+                    role_ids.iter().map(|id| &id.0).join(", ")
+                )));
+            }
+        }
+        validator.build()
+    }
+
     fn contains_role(&self, role_id: &RawRoleId) -> bool {
         self.roles.contains_key(role_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::book::fixtures::CONFIG;
+
+    use super::*;
+
+    fn conversation_id(room: u16, noun: u8, verb: u8, condition: u8) -> ConversationId {
+        ConversationId(
+            NounId(RoomId(RawRoomId(room)), RawNounId(noun)),
+            ConversationKey::from_nums(verb, condition),
+        )
+    }
+
+    const DUPLICATE_SHORT_NAMES_CONFIG: &str = r#"
+project_name: Test
+roles:
+  guard1:
+    name: First Guard
+    short_name: Guard
+  guard2:
+    name: Second Guard
+    short_name: Guard
+  ego:
+    name: Roger Wilco
+    short_name: Roger
+talkers: []
+verbs: []
+rooms: []
+"#;
+
+    #[test]
+    fn build_fails_on_duplicate_short_names() {
+        let config: BookConfig = serde_yml::from_str(DUPLICATE_SHORT_NAMES_CONFIG).unwrap();
+        let err = BookBuilder::new(config).unwrap().build().err().unwrap();
+        let message = err.to_string();
+        assert!(message.contains("\"Guard\""), "{}", message);
+        assert!(message.contains("guard1, guard2"), "{}", message);
+    }
+
+    #[test]
+    fn build_allows_duplicate_short_names_when_configured() {
+        let config: BookConfig = serde_yml::from_str(&format!(
+            "{}allow_duplicate_short_names: true\n",
+            DUPLICATE_SHORT_NAMES_CONFIG
+        ))
+        .unwrap();
+        let book = BookBuilder::new(config).unwrap().build().unwrap();
+        assert_eq!(book.roles().count(), 3);
+    }
+
+    #[test]
+    fn add_line_assigns_sequential_ids() {
+        let mut builder = BookBuilder::new(BookConfig::default()).unwrap();
+        let conv = conversation_id(100, 1, 2, 0);
+        let other_conv = conversation_id(100, 1, 3, 0);
+
+        let first = builder.add_line(conv, "First", 0).unwrap();
+        let other = builder.add_line(other_conv, "Elsewhere", 0).unwrap();
+        let second = builder.add_line(conv, "Second", 0).unwrap();
+        let third = builder.add_line(conv, "Third", 0).unwrap();
+
+        assert_eq!(
+            [first, second, third].map(|id| id.sequence_num()),
+            [1, 2, 3]
+        );
+        assert_eq!(other.sequence_num(), 1);
+        for id in [first, second, third] {
+            assert_eq!(id.0, conv);
+        }
+
+        let book = builder.build().unwrap();
+        let texts = book
+            .get_conversation(conv)
+            .unwrap()
+            .lines()
+            .map(|line| line.text().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["First", "Second", "Third"]);
+    }
+
+    #[test]
+    fn add_line_fails_when_sequence_ids_are_exhausted() {
+        let mut builder = BookBuilder::new(BookConfig::default()).unwrap();
+        let conv = conversation_id(100, 1, 2, 0);
+        for _ in 1..=255 {
+            builder.add_line(conv, "Line", 0).unwrap();
+        }
+        assert!(builder.add_line(conv, "One too many", 0).is_err());
+    }
+
+    #[test]
+    fn conversation_key_from_nums_round_trips() {
+        let key = ConversationKey::from_nums(3, 7);
+        assert_eq!(key.verb_num(), 3);
+        assert_eq!(key.condition_num(), 7);
+        assert_eq!(key, ConversationKey::new(RawVerbId(3), RawConditionId(7)));
+    }
+
+    #[test]
+    fn conversation_review_data_comes_from_config() {
+        let config: BookConfig = serde_yml::from_str(
+            r#"
+project_name: Test
+roles: {}
+talkers: []
+verbs: []
+rooms:
+  - id: 100
+    name: Bridge
+    nouns:
+      - id: 1
+        desc: Chair
+        conversations:
+          - verb: 2
+            condition: 0
+            note: Rewrite this.
+            needs_review: true
+"#,
+        )
+        .unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        let flagged = conversation_id(100, 1, 2, 0);
+        let unflagged = conversation_id(100, 1, 3, 0);
+        builder.add_line(flagged, "Line", 0).unwrap();
+        builder.add_line(unflagged, "Line", 0).unwrap();
+        let book = builder.build().unwrap();
+
+        let flagged = book.get_conversation(flagged).unwrap();
+        assert!(flagged.needs_review());
+        assert_eq!(flagged.note(), Some("Rewrite this."));
+        let unflagged = book.get_conversation(unflagged).unwrap();
+        assert!(!unflagged.needs_review());
+        assert_eq!(unflagged.note(), None);
+    }
+
+    #[test]
+    fn validate_only_reports_every_problem() {
+        let config: BookConfig = serde_yml::from_str(
+            r#"
+project_name: Test
+roles:
+  guard1:
+    name: First Guard
+    short_name: Guard
+  guard2:
+    name: Second Guard
+    short_name: Guard
+talkers:
+  - id: 1
+    role: guard1
+  - id: 2
+    role: missing
+verbs: []
+rooms:
+  - id: 100
+    name: Bridge
+    nouns:
+      - id: 1
+        desc: Chair
+        conversations:
+          - verb: 2
+            condition: 0
+"#,
+        )
+        .unwrap();
+        let builder = BookBuilder::new(config).unwrap();
+        let errors = builder
+            .validate_only()
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("\"Guard\""), "{}", errors[0]);
+        assert!(errors[1].contains("unknown role"), "{}", errors[1]);
+
+        // build() rejects the same configuration.
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn transform_lines_edits_every_line() {
+        let mut builder = BookBuilder::new(BookConfig::default()).unwrap();
+        let originals = [
+            (conversation_id(100, 1, 2, 0), "Hello there."),
+            (conversation_id(100, 1, 2, 0), "How are you?"),
+            (conversation_id(200, 3, 1, 0), "fine, thanks."),
+        ]
+        .into_iter()
+        .map(|(conv, text)| (builder.add_line(conv, text, 0).unwrap(), text))
+        .collect::<Vec<_>>();
+
+        let mut visited = Vec::new();
+        builder.transform_lines(|id, text| {
+            visited.push(id);
+            *text = text.to_uppercase();
+        });
+        let book = builder.build().unwrap();
+
+        assert_eq!(visited.len(), originals.len());
+        for (id, original) in originals {
+            let text = book.get_line(id).unwrap().text();
+            assert_ne!(text, original);
+            assert_eq!(text, original.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn play_order_overrides_key_order() {
+        let config: BookConfig = serde_yml::from_str(
+            r#"
+project_name: Test
+roles: {}
+talkers: []
+verbs: []
+rooms:
+  - id: 100
+    name: Bridge
+    nouns:
+      - id: 1
+        desc: Door
+        conversations:
+          - verb: 2
+            condition: 3
+            play_order: 0
+          - verb: 2
+            condition: 1
+            play_order: 1
+"#,
+        )
+        .unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        for condition in [1, 2, 3] {
+            builder
+                .add_line(conversation_id(100, 1, 2, condition), "Line", 0)
+                .unwrap();
+        }
+        let book = builder.build().unwrap();
+        let noun = book.nouns().next().unwrap();
+
+        let conditions = |conversations: Vec<crate::book::Conversation>| {
+            conversations
+                .iter()
+                .map(|conversation| conversation.id().condition_num())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(conditions(noun.conversations().collect()), vec![1, 2, 3]);
+        assert_eq!(
+            conditions(noun.conversations_in_play_order()),
+            vec![3, 1, 2]
+        );
+    }
+
+    #[test]
+    fn configured_conversation_without_lines_is_left_out() {
+        // The fixture configures a conversation with verb 2.
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        builder
+            .add_line(conversation_id(100, 1, 3, 0), "Line", 0)
+            .unwrap();
+        let book = builder.build().unwrap();
+
+        let noun = book.nouns().next().unwrap();
+        let verbs = noun
+            .conversations()
+            .map(|conversation| conversation.id().verb_num())
+            .collect::<Vec<_>>();
+        assert_eq!(verbs, vec![3]);
+    }
+
+    #[test]
+    fn audio_status_is_tracked_per_line() {
+        let mut builder = BookBuilder::new(BookConfig::default()).unwrap();
+        let conv = conversation_id(100, 1, 2, 0);
+        let lines = ["One.", "Two.", "Three.", "Four."]
+            .map(|text| builder.add_line(conv, text, 0).unwrap());
+        builder
+            .set_audio_status(lines[0], AudioStatus::Approved)
+            .unwrap()
+            .set_audio_status(lines[1], AudioStatus::Recorded)
+            .unwrap()
+            .set_audio_status(lines[2], AudioStatus::Approved)
+            .unwrap();
+        let missing_line = LineId(conversation_id(100, 1, 3, 0), RawSequenceId(1));
+        assert!(builder
+            .set_audio_status(missing_line, AudioStatus::Missing)
+            .is_err());
+        let book = builder.build().unwrap();
+
+        let with_status = |status| {
+            book.lines_with_status(status)
+                .map(|line| line.id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(with_status(AudioStatus::Approved), vec![lines[0], lines[2]]);
+        assert_eq!(with_status(AudioStatus::Recorded), vec![lines[1]]);
+        assert_eq!(with_status(AudioStatus::Missing), vec![]);
+        assert_eq!(book.get_line(lines[3]).unwrap().audio_status(), None);
+    }
+}
@@ -29,6 +29,22 @@ pub(super) struct ConditionEntry {
     pub desc: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ConversationEntry {
+    pub verb: RawVerbId,
+    pub condition: RawConditionId,
+    /// A free-text note about the conversation, for reviewers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// If true, this conversation has been flagged for review.
+    #[serde(default)]
+    pub needs_review: bool,
+    /// The position of this conversation when the noun's conversations are
+    /// played back or presented, if it differs from the key order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub play_order: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct NounEntry {
     pub id: RawNounId,
@@ -40,6 +56,13 @@ pub(super) struct NounEntry {
     /// If true, this room is not included in the final script.
     #[serde(default)]
     pub hidden: bool,
+    /// Additional data for conversations on this noun.
+    ///
+    /// This should generally be a map from conversation keys to data, but
+    /// there are several formats where compound keys are not natively
+    /// supported, so each entry holds its own verb and condition.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conversations: Vec<ConversationEntry>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,4 +96,8 @@ pub struct BookConfig {
     pub(super) talkers: Vec<TalkerEntry>,
     pub(super) verbs: Vec<VerbEntry>,
     pub(super) rooms: Vec<RoomEntry>,
+    /// If true, several roles may share the same short name. Otherwise,
+    /// building the book fails if any short name is ambiguous.
+    #[serde(default)]
+    pub(super) allow_duplicate_short_names: bool,
 }
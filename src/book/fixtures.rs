@@ -0,0 +1,30 @@
+//! Shared book fixtures for tests.
+
+/// A book config with one role, one verb, and one configured room, with a
+/// condition and a conversation with a note.
+pub const CONFIG: &str = r#"
+project_name: Test
+roles:
+  ego:
+    name: Roger Wilco
+    short_name: Roger
+talkers:
+  - id: 0
+    role: ego
+verbs:
+  - id: 2
+    name: Talk
+rooms:
+  - id: 100
+    name: Bridge
+    conditions:
+      - id: 3
+        desc: Alarm is on
+    nouns:
+      - id: 1
+        desc: Captain's chair
+        conversations:
+          - verb: 2
+            condition: 3
+            note: Check the timing
+"#;
@@ -0,0 +1,202 @@
+//! JSON views of single book entities, for commands that report on one
+//! entity at a time rather than exporting the whole book.
+//!
+//! Each entity is written as a self-contained object holding its ID string
+//! (in the form accepted by [`Book::resolve`](super::Book::resolve)), its own
+//! data, and the ID strings of its parent and immediate children.
+
+use serde_json::{json, Value};
+
+use super::{BookEntity, Condition, Conversation, Line, Noun, Room};
+
+impl Line<'_> {
+    /// Get this line as a JSON object. `text` holds the line with control
+    /// codes removed, and `raw_text` the line as stored in the book.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id().to_id_string(),
+            "conversation": self.id().0.to_id_string(),
+            "text": self.plain_text(),
+            "raw_text": self.text(),
+            "talker": self.talker_num(),
+            "role": self.try_role().map(|role| role.id().as_str().to_string()),
+        })
+    }
+}
+
+impl Conversation<'_> {
+    /// Get this conversation as a JSON object, listing the IDs of its lines.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id().to_id_string(),
+            "noun": self.noun().id().to_id_string(),
+            "verb": self.verb().map(|verb| verb.name().to_string()),
+            "condition": self.condition().map(|cond| cond.id().to_id_string()),
+            "note": self.note(),
+            "needs_review": self.needs_review(),
+            "play_order": self.play_order(),
+            "lines": self.lines().map(|line| line.id().to_id_string()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl Noun<'_> {
+    /// Get this noun as a JSON object, listing the IDs of its conversations.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id().to_id_string(),
+            "room": self.room().id().to_id_string(),
+            "desc": self.desc(),
+            "is_cutscene": self.is_cutscene(),
+            "conversations": self
+                .conversations()
+                .map(|conversation| conversation.id().to_id_string())
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl Condition<'_> {
+    /// Get this condition as a JSON object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id().to_id_string(),
+            "room": self.room().id().to_id_string(),
+            "desc": self.desc(),
+        })
+    }
+}
+
+impl Room<'_> {
+    /// Get this room as a JSON object, listing the IDs of its nouns and
+    /// conditions. `name` is omitted if the room has no name.
+    pub fn to_json(&self) -> Value {
+        let mut json = json!({
+            "id": self.id().to_id_string(),
+            "nouns": self.nouns().map(|noun| noun.id().to_id_string()).collect::<Vec<_>>(),
+            "conditions": self
+                .conditions()
+                .map(|cond| cond.id().to_id_string())
+                .collect::<Vec<_>>(),
+        });
+        if let Some(name) = self.try_name() {
+            json["name"] = name.into();
+        }
+        json
+    }
+}
+
+impl BookEntity<'_> {
+    /// Get the entity as a JSON object. See the module docs for the shape.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn to_json(&self) -> Value {
+        match self {
+            BookEntity::Room(room) => room.to_json(),
+            BookEntity::Condition(condition) => condition.to_json(),
+            BookEntity::Noun(noun) => noun.to_json(),
+            BookEntity::Conversation(conversation) => conversation.to_json(),
+            BookEntity::Line(line) => line.to_json(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book::{
+        builder::{BookBuilder, ConversationKey},
+        config::BookConfig,
+        fixtures::CONFIG,
+        Book, ConversationId, NounId, RawNounId, RawRoomId, RoomId,
+    };
+
+    use super::*;
+
+    fn book() -> Book {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        let conv = ConversationId(
+            NounId(RoomId(RawRoomId(100)), RawNounId(1)),
+            ConversationKey::from_nums(2, 3),
+        );
+        builder.add_line(conv, "|c|Hello?|c|", 0).unwrap();
+        builder.add_line(conv, "Anyone?", 0).unwrap();
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn line_json_includes_text_and_parent() {
+        let book = book();
+        let line = book.lines().next().unwrap();
+        assert_eq!(
+            line.to_json(),
+            json!({
+                "id": "line-100-1-2-3-1",
+                "conversation": "conv-100-1-2-3",
+                "text": "Hello?",
+                "raw_text": "|c|Hello?|c|",
+                "talker": 0,
+                "role": "ego",
+            })
+        );
+    }
+
+    #[test]
+    fn conversation_json_lists_line_ids() {
+        let book = book();
+        let conversation = book.conversations().next().unwrap();
+        assert_eq!(
+            conversation.to_json(),
+            json!({
+                "id": "conv-100-1-2-3",
+                "noun": "noun-100-1",
+                "verb": "Talk",
+                "condition": "cond-100-3",
+                "note": "Check the timing",
+                "needs_review": false,
+                "play_order": null,
+                "lines": ["line-100-1-2-3-1", "line-100-1-2-3-2"],
+            })
+        );
+    }
+
+    #[test]
+    fn room_json_omits_missing_name() {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        let conv = ConversationId(
+            NounId(RoomId(RawRoomId(200)), RawNounId(1)),
+            ConversationKey::from_nums(2, 0),
+        );
+        builder.add_line(conv, "Hello?", 0).unwrap();
+        let book = builder.build().unwrap();
+
+        let json = book.resolve("room-200").unwrap().to_json();
+        assert_eq!(
+            json,
+            json!({
+                "id": "room-200",
+                "nouns": ["noun-200-1"],
+                "conditions": ["cond-200-0"],
+            })
+        );
+        assert_eq!(
+            book.resolve("room-100").unwrap().to_json()["name"],
+            "Bridge"
+        );
+    }
+
+    #[test]
+    fn resolved_entities_round_trip_their_ids() {
+        let book = book();
+        for id in [
+            "room-100",
+            "cond-100-3",
+            "noun-100-1",
+            "conv-100-1-2-3",
+            "line-100-1-2-3-2",
+        ] {
+            let json = book.resolve(id).unwrap().to_json();
+            assert_eq!(json["id"], id);
+        }
+    }
+}
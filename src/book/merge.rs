@@ -0,0 +1,348 @@
+//! Merging of books built from separate configurations, and splitting a book
+//! into parts that can be merged back together.
+
+use std::collections::{btree_map, BTreeMap};
+
+use sci_utils::validation::{MultiValidator, ValidationError};
+
+use super::{Book, ConversationId, NounEntry, NounId, RawRoomId, RoomEntry, RoomId};
+
+/// Inserts every entry of `theirs` into `ours`. Entries present in both are
+/// passed to `merge_entry`, and any conflicts it reports are added to
+/// `validator`.
+fn merge_maps<K, V, F>(
+    validator: &mut MultiValidator,
+    ours: &mut BTreeMap<K, V>,
+    theirs: BTreeMap<K, V>,
+    mut merge_entry: F,
+) where
+    K: Ord,
+    F: FnMut(&K, &mut V, V) -> Result<(), ValidationError>,
+{
+    for (key, value) in theirs {
+        match ours.get_mut(&key) {
+            Some(existing) => {
+                validator.with_result(merge_entry(&key, existing, value));
+            }
+            None => {
+                ours.insert(key, value);
+            }
+        }
+    }
+}
+
+fn ensure_same<V: PartialEq>(
+    what: impl FnOnce() -> String,
+    ours: &V,
+    theirs: &V,
+) -> Result<(), ValidationError> {
+    if ours == theirs {
+        Ok(())
+    } else {
+        Err(format!("{} differs between books", what()).into())
+    }
+}
+
+/// Merges an optional value, where a missing value on either side defers to
+/// the other.
+fn merge_optional(
+    what: impl FnOnce() -> String,
+    ours: &mut Option<String>,
+    theirs: Option<String>,
+) -> Result<(), ValidationError> {
+    match (ours.as_ref(), theirs) {
+        (_, None) => Ok(()),
+        (None, theirs) => {
+            *ours = theirs;
+            Ok(())
+        }
+        (Some(ours), Some(theirs)) => ensure_same(what, ours, &theirs),
+    }
+}
+
+fn merge_nouns(
+    noun_id: NounId,
+    ours: &mut NounEntry,
+    theirs: NounEntry,
+) -> Result<(), ValidationError> {
+    let mut validator = MultiValidator::new();
+    validator.with_result(merge_optional(
+        || format!("Description of {:?}", noun_id),
+        &mut ours.desc,
+        theirs.desc,
+    ));
+    validator.with_result(ensure_same(
+        || format!("Cutscene flag of {:?}", noun_id),
+        &ours.is_cutscene,
+        &theirs.is_cutscene,
+    ));
+
+    // Conversations are never merged line by line, as there is no way to
+    // tell which book's lines should win.
+    let mut conflicts = Vec::new();
+    for (key, conversation) in theirs.conversations {
+        match ours.conversations.entry(key) {
+            btree_map::Entry::Vacant(vac) => {
+                vac.insert(conversation);
+            }
+            btree_map::Entry::Occupied(_) => conflicts.push(ConversationId(noun_id, key)),
+        }
+    }
+    if !conflicts.is_empty() {
+        validator.with_err(ValidationError::from(format!(
+            "Conversations defined in both books: {:?}",
+            conflicts
+        )));
+    }
+    validator.build()
+}
+
+fn merge_rooms(
+    room_id: RawRoomId,
+    ours: &mut RoomEntry,
+    theirs: RoomEntry,
+) -> Result<(), ValidationError> {
+    let mut validator = MultiValidator::new();
+    validator.with_result(merge_optional(
+        || format!("Name of room {}", room_id.0),
+        &mut ours.name,
+        theirs.name,
+    ));
+    merge_maps(
+        &mut validator,
+        &mut ours.conditions,
+        theirs.conditions,
+        |condition_id, ours, theirs| match (ours.builder.desc(), theirs.builder.desc()) {
+            (_, None) => Ok(()),
+            (None, Some(_)) => {
+                *ours = theirs;
+                Ok(())
+            }
+            (Some(ours), Some(theirs)) => ensure_same(
+                || format!("Condition {} of room {}", condition_id.0, room_id.0),
+                &ours,
+                &theirs,
+            ),
+        },
+    );
+    merge_maps(
+        &mut validator,
+        &mut ours.nouns,
+        theirs.nouns,
+        |&noun_id, ours, theirs| merge_nouns(NounId(RoomId(room_id), noun_id), ours, theirs),
+    );
+    validator.build()
+}
+
+impl Book {
+    /// Combines two books into one, such as books built from separate
+    /// config files for the same project.
+    ///
+    /// Roles, talkers, verbs, rooms, conditions and nouns present in only
+    /// one book are copied over. Entities present in both must agree, except
+    /// that unset names and descriptions are filled in from the other book.
+    /// A conversation may only be defined by one of the books. All conflicts
+    /// are reported together.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn merge(mut self, other: Book) -> Result<Book, ValidationError> {
+        let mut validator = MultiValidator::new();
+        validator.with_result(ensure_same(
+            || "Project name".to_string(),
+            &self.project_name,
+            &other.project_name,
+        ));
+        merge_maps(
+            &mut validator,
+            &mut self.roles,
+            other.roles,
+            |role_id, ours, theirs| ensure_same(|| format!("Role {:?}", role_id.0), ours, &theirs),
+        );
+        merge_maps(
+            &mut validator,
+            &mut self.talkers,
+            other.talkers,
+            |talker_id, ours, theirs| {
+                ensure_same(|| format!("Talker {}", talker_id.0), ours, &theirs)
+            },
+        );
+        merge_maps(
+            &mut validator,
+            &mut self.verbs,
+            other.verbs,
+            |verb_id, ours, theirs| ensure_same(|| format!("Verb {}", verb_id.0), ours, &theirs),
+        );
+        merge_maps(
+            &mut validator,
+            &mut self.rooms,
+            other.rooms,
+            |&room_id, ours, theirs| merge_rooms(room_id, ours, theirs),
+        );
+        validator.build()?;
+        Ok(self)
+    }
+
+    /// Splits the book into one book per room, such as to edit rooms
+    /// separately before combining them again with [`Book::merge`].
+    ///
+    /// Every part has all of the roles, talkers and verbs of this book, so
+    /// the roles of its lines and the verbs of its conversations can still be
+    /// looked up.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn split_by_room(&self) -> BTreeMap<RoomId, Book> {
+        self.rooms
+            .iter()
+            .map(|(&room_id, room)| {
+                let part = Book {
+                    project_name: self.project_name.clone(),
+                    roles: self.roles.clone(),
+                    talkers: self.talkers.clone(),
+                    verbs: self.verbs.clone(),
+                    rooms: BTreeMap::from([(room_id, room.clone())]),
+                };
+                (RoomId(room_id), part)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book::{
+        builder::{BookBuilder, ConversationKey},
+        config::BookConfig,
+        RawNounId,
+    };
+
+    use super::*;
+
+    fn book(config: &str, lines: &[(u16, u8, u8, &str)]) -> Book {
+        let config: BookConfig = serde_yml::from_str(config).unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        for &(room, noun, talker, text) in lines {
+            let conv = ConversationId(
+                NounId(RoomId(RawRoomId(room)), RawNounId(noun)),
+                ConversationKey::from_nums(2, 0),
+            );
+            builder.add_line(conv, text, talker).unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    const EGO_CONFIG: &str = r#"
+project_name: Test
+roles:
+  ego:
+    name: Roger Wilco
+    short_name: Roger
+talkers:
+  - id: 0
+    role: ego
+verbs: []
+rooms: []
+"#;
+
+    const NARRATOR_CONFIG: &str = r#"
+project_name: Test
+roles:
+  ego:
+    name: Roger Wilco
+    short_name: Roger
+  narrator:
+    name: Narrator
+    short_name: Narrator
+talkers:
+  - id: 0
+    role: ego
+  - id: 1
+    role: narrator
+verbs: []
+rooms: []
+"#;
+
+    #[test]
+    fn merge_combines_disjoint_entities() {
+        let first = book(EGO_CONFIG, &[(100, 1, 0, "Hello?")]);
+        let second = book(
+            NARRATOR_CONFIG,
+            &[(100, 2, 1, "Nobody answers."), (200, 1, 0, "Anyone?")],
+        );
+
+        let merged = first.merge(second).unwrap();
+        assert_eq!(merged.rooms().count(), 2);
+        assert_eq!(merged.nouns().count(), 3);
+        assert_eq!(merged.roles().count(), 2);
+        assert_eq!(
+            merged.lines().map(|line| line.text()).collect::<Vec<_>>(),
+            vec!["Hello?", "Nobody answers.", "Anyone?"]
+        );
+    }
+
+    #[test]
+    fn merge_reports_all_conflicts() {
+        let first = book(EGO_CONFIG, &[(100, 1, 0, "Hello?")]);
+        let second = book(
+            &NARRATOR_CONFIG
+                .replace("project_name: Test", "project_name: Other")
+                .replace("short_name: Roger", "short_name: Wilco"),
+            &[(100, 1, 1, "Nobody answers.")],
+        );
+
+        let Err(err) = first.merge(second) else {
+            panic!("expected conflicts");
+        };
+        let message = err.to_string();
+        assert!(message.contains("Project name"), "{}", message);
+        assert!(message.contains("Role \"ego\""), "{}", message);
+        assert!(
+            message.contains("Conversations defined in both books"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn split_parts_merge_back_into_book() {
+        let original = book(
+            NARRATOR_CONFIG,
+            &[
+                (100, 1, 0, "Hello?"),
+                (100, 2, 1, "Nobody answers."),
+                (200, 1, 0, "Anyone?"),
+            ],
+        );
+        let lines = |book: &Book| {
+            book.lines()
+                .map(|line| {
+                    (
+                        line.id(),
+                        line.text().to_string(),
+                        line.role().id().as_str().to_string(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let parts = original.split_by_room();
+        assert_eq!(
+            parts.keys().map(|id| id.room_num()).collect::<Vec<_>>(),
+            vec![100, 200]
+        );
+        for (room_id, part) in &parts {
+            assert_eq!(
+                part.rooms().map(|room| room.id()).collect::<Vec<_>>(),
+                vec![*room_id]
+            );
+            assert_eq!(part.roles().count(), 2);
+        }
+        assert_eq!(
+            lines(&parts[&RoomId(RawRoomId(200))]),
+            lines(&original)[2..]
+        );
+
+        let merged = parts
+            .into_values()
+            .reduce(|merged, part| merged.merge(part).unwrap())
+            .unwrap();
+        assert_eq!(lines(&merged), lines(&original));
+    }
+}
@@ -0,0 +1,123 @@
+//! Parsing of the inline control codes in SCI message text.
+
+/// A piece of SCI message text: either literal text, or a control code
+/// such as `|c1|` (color) or `|f|` (font reset).
+pub enum MessageSegment<'a> {
+    Text(&'a str),
+    Control(char, Option<u32>),
+}
+
+fn split_first_char(text: &str) -> Option<(char, &str)> {
+    let mut chars = text.chars();
+    let first = chars.next()?;
+    Some((first, chars.as_str()))
+}
+
+/// Parses a control code following its opening `|`.
+///
+/// Returns the control character, its value, and the length of the code up
+/// to and including the closing `|`, or None if `text` does not start with
+/// a well-formed control code.
+fn parse_control(text: &str) -> Option<(char, Option<u32>, usize)> {
+    let (control, rest) = split_first_char(text)?;
+    if !control.is_ascii_alphabetic() {
+        return None;
+    }
+    let (value, _) = rest.split_once('|')?;
+    let len = control.len_utf8() + value.len() + 1;
+    let value = if value.is_empty() {
+        None
+    } else {
+        Some(value.parse().ok()?)
+    };
+    Some((control, value, len))
+}
+
+/// Splits message text into literal text and control code segments.
+///
+/// A `|` that does not start a well-formed control code (a letter, an
+/// optional number, and a closing `|`) is kept as literal text.
+pub fn parse_message_text(text: &str) -> Vec<MessageSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut literal_start = 0;
+    let mut search_start = 0;
+    while let Some(offset) = text[search_start..].find('|') {
+        let control_start = search_start + offset;
+        let Some((control, value, len)) = parse_control(&text[control_start + 1..]) else {
+            search_start = control_start + 1;
+            continue;
+        };
+        if literal_start < control_start {
+            segments.push(MessageSegment::Text(&text[literal_start..control_start]));
+        }
+        segments.push(MessageSegment::Control(control, value));
+        literal_start = control_start + 1 + len;
+        search_start = literal_start;
+    }
+    if literal_start < text.len() {
+        segments.push(MessageSegment::Text(&text[literal_start..]));
+    }
+
+    segments
+}
+
+/// Returns the message text with all control codes removed.
+pub fn plain_text(text: &str) -> String {
+    plain_text_with(text, |_, _| None)
+}
+
+/// Counts the whitespace-separated words in the message text, ignoring
+/// control codes.
+pub fn word_count(text: &str) -> usize {
+    plain_text(text).split_whitespace().count()
+}
+
+/// Returns the message text with each control code replaced by the result
+/// of `render_control`, called with the control character and value.
+/// Control codes for which it returns `None` are dropped.
+pub fn plain_text_with<F>(text: &str, render_control: F) -> String
+where
+    F: Fn(char, Option<u32>) -> Option<String>,
+{
+    let mut output = String::new();
+    for segment in parse_message_text(text) {
+        match segment {
+            MessageSegment::Text(text) => output.push_str(text),
+            MessageSegment::Control(control, value) => {
+                if let Some(rendered) = render_control(control, value) {
+                    output.push_str(&rendered);
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_strips_control_codes() {
+        assert_eq!(plain_text("|f5|Space Quest 5:|f|"), "Space Quest 5:");
+        assert_eq!(plain_text("Hel|c1|lo|c| there"), "Hello there");
+        assert_eq!(plain_text("No controls."), "No controls.");
+    }
+
+    #[test]
+    fn malformed_controls_are_literal_text() {
+        assert_eq!(plain_text("A 50|50 chance."), "A 50|50 chance.");
+        assert_eq!(plain_text("Trailing|"), "Trailing|");
+        assert_eq!(plain_text("|cx|Odd value"), "|cx|Odd value");
+        assert_eq!(plain_text("|"), "|");
+        assert_eq!(plain_text("1|2 |c1|red|c|"), "1|2 red");
+    }
+
+    #[test]
+    fn plain_text_with_renders_chosen_controls() {
+        let rendered = plain_text_with("|f2|Hello|f| |c1|there|c|", |control, value| {
+            (control == 'f').then(|| format!("<f{}>", value.unwrap_or(0)))
+        });
+        assert_eq!(rendered, "<f2>Hello<f0> there");
+    }
+}
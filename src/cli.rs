@@ -1,11 +1,18 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
-use sci_resources::{file::open_game_resources, ResourceId, ResourceType};
-use sci_utils::data_writer::{DataWriter, IoDataWriter};
-
+use sci_resources::{
+    file::{open_game_resources, patch_file_name, write_new_patch},
+    ResourceId, ResourceType,
+};
+
+mod book;
+mod dump;
+#[cfg(test)]
+pub(crate) mod fixtures;
 mod gen;
 mod msg;
+mod script;
 
 #[derive(Parser)]
 struct ListResources {
@@ -51,17 +58,9 @@ impl ExtractResourceAsPatch {
         let contents = resource_set
             .get_resource(&resource_id)
             .ok_or_else(|| anyhow::anyhow!("Resource not found: {:?}", resource_id))?;
-        let ext = match self.resource_type {
-            ResourceType::Script => "SCR",
-            ResourceType::Heap => "HEP",
-            _ => {
-                anyhow::bail!("Unsupported resource type");
-            }
-        };
-
         let out_root = self.output_dir.as_ref().unwrap_or(&self.root_dir);
 
-        let filename = out_root.join(format!("{0}.{1}", self.resource_id, ext));
+        let filename = out_root.join(patch_file_name(resource_id));
         if self.dry_run {
             eprintln!(
                 "DRY_RUN: Writing resource {restype:?}:{resid} to {filename:?}",
@@ -76,17 +75,11 @@ impl ExtractResourceAsPatch {
                 resid = self.resource_id,
                 filename = filename
             );
-            {
-                let mut patch_file = IoDataWriter::new(
-                    std::fs::OpenOptions::new()
-                        .write(true)
-                        .create_new(true)
-                        .open(self.root_dir.join(filename))?,
-                );
-
-                patch_file.write_u8(self.resource_type.into())?;
-                patch_file.write_u8(0)?; // Header Size
-                patch_file.write_block(&contents.load_data()?)?;
+            if let Err(e) = write_new_patch(out_root, resource_id, &contents.load_data()?) {
+                if e.kind() == std::io::ErrorKind::AlreadyExists {
+                    anyhow::bail!("Patch file already exists: {:?}", filename);
+                }
+                return Err(e.into());
             }
         }
 
@@ -102,17 +95,35 @@ struct DumpResource {
     resource_type: ResourceType,
     #[clap(index = 3)]
     resource_id: u16,
+    /// Always print a hex dump, even if the resource type can be decoded.
+    #[clap(long, default_value = "false")]
+    raw: bool,
 }
 
 impl DumpResource {
     fn run(&self) -> anyhow::Result<()> {
         let resource_set = open_game_resources(&self.root_dir)?;
         let resource_id = ResourceId::new(self.resource_type, self.resource_id);
-        let res = resource_set
-            .get_resource(&resource_id)
-            .ok_or_else(|| anyhow::anyhow!("Resource not found: {:?}", resource_id))?;
-        let data = res.load_data()?;
-        sci_utils::debug::hex_dump(&data, 0);
+        dump::dump_resource(std::io::stdout(), &resource_set, &resource_id, self.raw)
+    }
+}
+
+#[derive(Parser)]
+struct CheckResources {
+    #[clap(index = 1)]
+    root_dir: PathBuf,
+}
+
+impl CheckResources {
+    fn run(&self) -> anyhow::Result<()> {
+        let resource_set = open_game_resources(&self.root_dir)?;
+        let problems = resource_set.check_integrity();
+        for problem in &problems {
+            println!("{:?}: {}", problem.id, problem.reason);
+        }
+        if !problems.is_empty() {
+            anyhow::bail!("Found {} problem(s) with resources", problems.len());
+        }
         Ok(())
     }
 }
@@ -123,6 +134,7 @@ enum ResourceCommand {
     List(ListResources),
     ExtractAsPatch(ExtractResourceAsPatch),
     Dump(DumpResource),
+    Check(CheckResources),
 }
 
 impl ResourceCommand {
@@ -131,6 +143,7 @@ impl ResourceCommand {
             ResourceCommand::List(list) => list.run()?,
             ResourceCommand::ExtractAsPatch(extract) => extract.run()?,
             ResourceCommand::Dump(dump) => dump.run()?,
+            ResourceCommand::Check(check) => check.run()?,
         }
         Ok(())
     }
@@ -156,6 +169,10 @@ enum Category {
     Message(msg::Messages),
     #[clap(name = "gen")]
     Generate(gen::Generate),
+    #[clap(name = "book")]
+    Book(book::Books),
+    #[clap(name = "script")]
+    Script(script::Scripts),
 }
 
 impl Category {
@@ -164,6 +181,8 @@ impl Category {
             Category::Resource(res) => res.run(),
             Category::Message(msg) => msg.run(),
             Category::Generate(gen) => gen.run(),
+            Category::Book(book) => book.run(),
+            Category::Script(script) => script.run(),
         }
     }
 }
@@ -0,0 +1,762 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use sci_resources::{
+    file::{open_game_resources, ResourceSet},
+    types::msg::parse_message_resource,
+    ResourceType,
+};
+use serde::Serialize;
+
+use crate::{
+    book::{builder::BookBuilder, config::BookConfig, text, Book, LineId},
+    output::{book::BookFile, wrap_rich_text},
+};
+
+use super::gen::convert_message_text_to_rich_text;
+
+/// The name of the book file within a project directory.
+const BOOK_FILE_NAME: &str = "book.json";
+
+/// The default speaking rate used to estimate how long lines take to read.
+const DEFAULT_WORDS_PER_MINUTE: u32 = 150;
+
+/// Loads the book config at `config_path`, or the default config if there
+/// is none.
+fn load_config(config_path: Option<&Path>) -> anyhow::Result<BookConfig> {
+    match config_path {
+        Some(config_path) => {
+            let config: BookConfig = serde_yml::from_reader(std::fs::File::open(config_path)?)?;
+            eprintln!("Loaded config from {:?}", config_path);
+            Ok(config)
+        }
+        None => Ok(BookConfig::default()),
+    }
+}
+
+/// Creates a book builder holding all of the message resources in the
+/// resource set.
+fn book_builder(resource_set: &ResourceSet, config: BookConfig) -> anyhow::Result<BookBuilder> {
+    let mut builder = BookBuilder::new(config)?;
+    for res in resource_set.resources_of_type(ResourceType::Message) {
+        let msg_resources = parse_message_resource(res.load_data()?)?;
+        for (msg_id, record) in msg_resources.messages() {
+            builder.add_message(res.id().resource_num(), msg_id, record)?;
+        }
+    }
+    Ok(builder)
+}
+
+/// Builds a book from all of the message resources in the resource set.
+fn build_book(resource_set: &ResourceSet, config: BookConfig) -> anyhow::Result<Book> {
+    Ok(book_builder(resource_set, config)?.build()?)
+}
+
+/// Reads the book file in the project directory, if there is one.
+fn read_book_file(project_dir: &Path) -> anyhow::Result<Option<BookFile>> {
+    let book_path = project_dir.join(BOOK_FILE_NAME);
+    if !book_path.exists() {
+        return Ok(None);
+    }
+    let reader = std::io::BufReader::new(std::fs::File::open(&book_path)?);
+    Ok(Some(serde_json::from_reader(reader)?))
+}
+
+/// Copies the audio status of each line in `previous` to the line with the
+/// same ID in `builder`. Lines whose text has changed since are left
+/// without a status, as their recordings no longer match.
+///
+/// Returns the number of statuses restored.
+fn restore_audio_statuses(builder: &mut BookBuilder, previous: &BookFile) -> anyhow::Result<usize> {
+    let mut statuses = BTreeMap::new();
+    for room in &previous.rooms {
+        for noun in &room.nouns {
+            for conversation in &noun.conversations {
+                for line in &conversation.lines {
+                    if let Some(status) = line.audio_status {
+                        let key = (
+                            room.id,
+                            noun.id,
+                            conversation.verb,
+                            conversation.condition,
+                            line.sequence,
+                        );
+                        statuses.insert(key, (line.text.as_str(), status));
+                    }
+                }
+            }
+        }
+    }
+
+    let restored = builder
+        .lines()
+        .filter_map(|(id, text)| {
+            let key = (
+                id.room_num(),
+                id.noun_num(),
+                id.verb_num(),
+                id.condition_num(),
+                id.sequence_num(),
+            );
+            match statuses.get(&key) {
+                Some(&(previous_text, status)) if previous_text == text => Some((id, status)),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    for &(id, status) in &restored {
+        builder.set_audio_status(id, status)?;
+    }
+    Ok(restored.len())
+}
+
+/// Returns the IDs of all lines whose talker is not assigned to a role.
+fn unattributed_lines(book: &Book) -> Vec<(LineId, u8)> {
+    book.lines()
+        .filter(|line| line.try_role().is_none())
+        .map(|line| (line.id(), line.talker_num()))
+        .collect()
+}
+
+/// Writes the book file for `book` into the project directory.
+///
+/// The file is written to a temporary file first, and then moved into
+/// place, so an existing project is never left with a partially written
+/// book.
+fn write_book_file(project_dir: &Path, book: &Book) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(project_dir)?;
+    let book_path = project_dir.join(BOOK_FILE_NAME);
+    let tmp_path = project_dir.join(format!("{}.tmp", BOOK_FILE_NAME));
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(&tmp_path)?);
+    book.write_export_json_to(&mut writer)?;
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(&tmp_path, &book_path)?;
+    Ok(book_path)
+}
+
+#[derive(Parser)]
+struct ImportBook {
+    #[clap(index = 1)]
+    root_dir: PathBuf,
+    #[clap(index = 2)]
+    project_dir: PathBuf,
+    #[clap(long = "config")]
+    config_path: Option<PathBuf>,
+}
+
+impl ImportBook {
+    fn run(&self) -> anyhow::Result<()> {
+        let config = load_config(self.config_path.as_deref())?;
+        let resource_set = open_game_resources(&self.root_dir)?;
+        let mut builder = book_builder(&resource_set, config)?;
+        // Audio statuses are only kept in the book file, so carry them over
+        // from the existing one.
+        if let Some(previous) = read_book_file(&self.project_dir)? {
+            let restored = restore_audio_statuses(&mut builder, &previous)?;
+            eprintln!("Kept the audio status of {} lines", restored);
+        }
+        let book = builder.build()?;
+
+        let unattributed = unattributed_lines(&book);
+        for (line_id, talker) in &unattributed {
+            eprintln!(
+                "Line {:?}: talker {} has no configured role",
+                line_id, talker
+            );
+        }
+        if !unattributed.is_empty() {
+            eprintln!(
+                "{} of {} lines could not be attributed to a role",
+                unattributed.len(),
+                book.lines().count()
+            );
+        }
+
+        let book_path = write_book_file(&self.project_dir, &book)?;
+        eprintln!("Wrote book to {:?}", book_path);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct RoomStats {
+    room: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    conversations: usize,
+    lines: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct RoleStats {
+    role: String,
+    /// The name of the role. Book files only hold role IDs, so this is None
+    /// for stats read from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    lines: usize,
+    words: usize,
+    /// The estimated time to read all of the role's lines, in seconds.
+    duration_secs: f64,
+}
+
+/// A summary of the amount of text in a book.
+#[derive(Serialize, Debug)]
+struct BookStats {
+    lines: usize,
+    words: usize,
+    words_per_minute: u32,
+    rooms: Vec<RoomStats>,
+    roles: Vec<RoleStats>,
+}
+
+impl BookStats {
+    /// Collects stats for `book`, estimating durations at `words_per_minute`.
+    fn new(book: &Book, words_per_minute: u32) -> Self {
+        let duration_secs = |words: usize| words as f64 * 60.0 / words_per_minute as f64;
+        BookStats {
+            lines: book.line_count(),
+            words: book.lines().map(|line| line.word_count()).sum(),
+            words_per_minute,
+            rooms: book
+                .rooms()
+                .map(|room| RoomStats {
+                    room: room.id().room_num(),
+                    name: room.try_name().map(ToString::to_string),
+                    conversations: room.nouns().map(|noun| noun.conversations().count()).sum(),
+                    lines: room
+                        .nouns()
+                        .flat_map(|noun| noun.conversations())
+                        .map(|conversation| conversation.line_count())
+                        .sum(),
+                })
+                .collect(),
+            roles: book
+                .roles()
+                .map(|role| {
+                    let words = role.word_count();
+                    RoleStats {
+                        role: role.id().as_str().to_string(),
+                        name: Some(role.name().to_string()),
+                        lines: role.line_count(),
+                        words,
+                        duration_secs: duration_secs(words),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Collects stats for the book held in `book_file`, estimating durations
+    /// at `words_per_minute`.
+    fn from_book_file(book_file: &BookFile, words_per_minute: u32) -> Self {
+        let duration_secs = |words: usize| words as f64 * 60.0 / words_per_minute as f64;
+        let lines = || {
+            book_file
+                .rooms
+                .iter()
+                .flat_map(|room| &room.nouns)
+                .flat_map(|noun| &noun.conversations)
+                .flat_map(|conversation| &conversation.lines)
+        };
+        let mut roles = BTreeMap::new();
+        for line in lines() {
+            if let Some(role) = &line.role {
+                let (lines, words) = roles.entry(role.as_str()).or_insert((0, 0));
+                *lines += 1;
+                *words += text::word_count(&line.text);
+            }
+        }
+        BookStats {
+            lines: lines().count(),
+            words: lines().map(|line| text::word_count(&line.text)).sum(),
+            words_per_minute,
+            rooms: book_file
+                .rooms
+                .iter()
+                .map(|room| RoomStats {
+                    room: room.id,
+                    name: room.name.clone(),
+                    conversations: room.nouns.iter().map(|noun| noun.conversations.len()).sum(),
+                    lines: room
+                        .nouns
+                        .iter()
+                        .flat_map(|noun| &noun.conversations)
+                        .map(|conversation| conversation.lines.len())
+                        .sum(),
+                })
+                .collect(),
+            roles: roles
+                .into_iter()
+                .map(|(role, (lines, words))| RoleStats {
+                    role: role.to_string(),
+                    name: None,
+                    lines,
+                    words,
+                    duration_secs: duration_secs(words),
+                })
+                .collect(),
+        }
+    }
+
+    fn write_text<W: Write>(&self, mut out: W) -> anyhow::Result<()> {
+        fn duration(secs: f64) -> String {
+            let secs = secs.round() as u64;
+            format!("{}:{:02}", secs / 60, secs % 60)
+        }
+
+        writeln!(out, "Total: {} lines, {} words", self.lines, self.words)?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{:<6} {:<24} {:>13} {:>6}",
+            "Room", "Name", "Conversations", "Lines"
+        )?;
+        for room in &self.rooms {
+            writeln!(
+                out,
+                "{:<6} {:<24} {:>13} {:>6}",
+                room.room,
+                room.name.as_deref().unwrap_or(""),
+                room.conversations,
+                room.lines
+            )?;
+        }
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{:<12} {:<24} {:>6} {:>6} {:>8}",
+            "Role", "Name", "Lines", "Words", "Duration"
+        )?;
+        for role in &self.roles {
+            writeln!(
+                out,
+                "{:<12} {:<24} {:>6} {:>6} {:>8}",
+                role.role,
+                role.name.as_deref().unwrap_or(""),
+                role.lines,
+                role.words,
+                duration(role.duration_secs)
+            )?;
+        }
+        writeln!(
+            out,
+            "(Durations at {} words per minute)",
+            self.words_per_minute
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum StatsFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Parser)]
+struct Stats {
+    /// The project directory holding the book file.
+    #[clap(index = 1, required_unless_present = "game_dir")]
+    project_dir: Option<PathBuf>,
+    /// Build the book from the message resources of the game in this
+    /// directory, instead of reading a project's book file.
+    #[clap(long = "game", conflicts_with = "project_dir")]
+    game_dir: Option<PathBuf>,
+    /// The book config to use with `--game`.
+    #[clap(long = "config", requires = "game_dir")]
+    config_path: Option<PathBuf>,
+    #[clap(long, value_enum, default_value_t)]
+    format: StatsFormat,
+    /// The speaking rate used to estimate line durations.
+    #[clap(long, default_value_t = DEFAULT_WORDS_PER_MINUTE, value_parser = clap::value_parser!(u32).range(1..))]
+    wpm: u32,
+}
+
+impl Stats {
+    fn run(&self) -> anyhow::Result<()> {
+        let stats = match (&self.project_dir, &self.game_dir) {
+            (_, Some(game_dir)) => {
+                let config = load_config(self.config_path.as_deref())?;
+                let resource_set = open_game_resources(game_dir)?;
+                BookStats::new(&build_book(&resource_set, config)?, self.wpm)
+            }
+            (Some(project_dir), None) => {
+                let book_file = read_book_file(project_dir)?
+                    .ok_or_else(|| anyhow::anyhow!("No book file in {:?}", project_dir))?;
+                BookStats::from_book_file(&book_file, self.wpm)
+            }
+            (None, None) => unreachable!("clap requires a project or game directory"),
+        };
+        match self.format {
+            StatsFormat::Text => stats.write_text(std::io::stdout())?,
+            StatsFormat::Json => {
+                serde_json::to_writer_pretty(std::io::stdout(), &stats)?;
+                println!();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+struct FindDuplicates {
+    #[clap(index = 1)]
+    root_dir: PathBuf,
+    #[clap(long = "config")]
+    config_path: Option<PathBuf>,
+}
+
+impl FindDuplicates {
+    fn run(&self) -> anyhow::Result<()> {
+        let config = load_config(self.config_path.as_deref())?;
+        let resource_set = open_game_resources(&self.root_dir)?;
+        let book = build_book(&resource_set, config)?;
+        let duplicates = book.duplicate_texts();
+        for (text, lines) in &duplicates {
+            println!("{:?}", text);
+            for line_id in lines {
+                println!("  {:?}", line_id);
+            }
+        }
+        eprintln!("{} texts are used by more than one line", duplicates.len());
+        Ok(())
+    }
+}
+
+/// Writes the text of each line in the book file, grouped by room and
+/// conversation, with the text wrapped at `width` columns.
+fn write_preview<W: Write>(
+    book_file: &BookFile,
+    room_filter: Option<u16>,
+    width: usize,
+    mut out: W,
+) -> anyhow::Result<()> {
+    for room in &book_file.rooms {
+        if room_filter.is_some_and(|id| id != room.id) {
+            continue;
+        }
+        match &room.name {
+            Some(name) => writeln!(out, "Room {}: {}", room.id, name)?,
+            None => writeln!(out, "Room {}", room.id)?,
+        }
+        for noun in &room.nouns {
+            for conversation in &noun.conversations {
+                writeln!(
+                    out,
+                    "  Noun {}, verb {}, condition {}:",
+                    noun.id, conversation.verb, conversation.condition
+                )?;
+                for line in &conversation.lines {
+                    match &line.role {
+                        Some(role) => writeln!(out, "    {}:", role)?,
+                        None => writeln!(out, "    Talker {}:", line.talker)?,
+                    }
+                    let ctxt = format!(
+                        "room {}, noun {}, verb {}, condition {}, sequence {}",
+                        room.id, noun.id, conversation.verb, conversation.condition, line.sequence
+                    );
+                    let text = convert_message_text_to_rich_text(&ctxt, &line.text);
+                    for row in wrap_rich_text(&text, width) {
+                        writeln!(out, "      {}", row)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Parser)]
+struct Preview {
+    #[clap(index = 1)]
+    project_dir: PathBuf,
+    /// Only show the lines of this room.
+    #[clap(short = 'r', long)]
+    room: Option<u16>,
+    /// The column to wrap line text at, or 0 to not wrap.
+    #[clap(long, default_value_t = 80)]
+    width: usize,
+}
+
+impl Preview {
+    fn run(&self) -> anyhow::Result<()> {
+        let book_file = read_book_file(&self.project_dir)?
+            .ok_or_else(|| anyhow::anyhow!("No book file in {:?}", self.project_dir))?;
+        write_preview(&book_file, self.room, self.width, std::io::stdout().lock())
+    }
+}
+
+#[derive(Subcommand)]
+enum BookCommand {
+    Import(ImportBook),
+    /// Prints line and word counts for the book, by room and by role.
+    Stats(Stats),
+    /// Lists lines that share the same text, such as from copy-paste errors.
+    FindDuplicates(FindDuplicates),
+    /// Prints the text of the book's lines, wrapped for reading in a
+    /// terminal.
+    Preview(Preview),
+}
+
+#[derive(Parser)]
+pub struct Books {
+    #[clap(subcommand)]
+    book_cmd: BookCommand,
+}
+
+impl Books {
+    pub fn run(&self) -> anyhow::Result<()> {
+        match &self.book_cmd {
+            BookCommand::Import(cmd) => cmd.run()?,
+            BookCommand::Stats(cmd) => cmd.run()?,
+            BookCommand::FindDuplicates(cmd) => cmd.run()?,
+            BookCommand::Preview(cmd) => cmd.run()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sci_resources::{types::msg::MessageFormatVersion, ResourceId};
+
+    use crate::{
+        book::AudioStatus,
+        cli::fixtures::{message_resource, resource_set},
+    };
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+project_name: Fixture Game
+roles:
+  ego:
+    name: Roger Wilco
+    short_name: Roger
+talkers:
+  - id: 0
+    role: ego
+verbs:
+  - id: 1
+    name: Look
+  - id: 2
+    name: Talk
+rooms: []
+"#;
+
+    fn fixture_game() -> ResourceSet {
+        resource_set(vec![
+            (
+                ResourceId::new(ResourceType::Message, 100),
+                message_resource(&[
+                    (1, 2, 0, 1, 0, "Hello?"),
+                    (1, 2, 0, 2, 5, "Who goes there?"),
+                ]),
+            ),
+            (
+                ResourceId::new(ResourceType::Message, 200),
+                message_resource(&[(3, 1, 0, 1, 0, "A mop.")]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn import_produces_readable_book() {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let book = build_book(&fixture_game(), config).unwrap();
+        let book_file = BookFile::from_book(&book);
+
+        let json = serde_json::to_string_pretty(&book_file).unwrap();
+        let book_file: BookFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(book_file.project_name, "Fixture Game");
+        let rooms = book_file
+            .rooms
+            .iter()
+            .map(|room| room.id)
+            .collect::<Vec<_>>();
+        assert_eq!(rooms, vec![100, 200]);
+
+        let conversation = &book_file.rooms[0].nouns[0].conversations[0];
+        assert_eq!((conversation.verb, conversation.condition), (2, 0));
+        let lines = conversation
+            .lines
+            .iter()
+            .map(|line| (line.sequence, line.role.as_deref(), line.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![(1, Some("ego"), "Hello?"), (2, None, "Who goes there?")]
+        );
+    }
+
+    #[test]
+    fn book_round_trips_to_message_resources() {
+        let game = fixture_game();
+        let book = build_book(&game, BookConfig::default()).unwrap();
+
+        for room in book.rooms() {
+            let original = game
+                .get_resource(&ResourceId::new(
+                    ResourceType::Message,
+                    room.id().room_num(),
+                ))
+                .unwrap()
+                .load_data()
+                .unwrap();
+            let rebuilt = book
+                .to_message_resource(room.id(), MessageFormatVersion::V4)
+                .unwrap();
+
+            // The fixture's records all carry a non-empty reference, so this
+            // also checks that references are written back.
+            let messages = |data| {
+                parse_message_resource(data)
+                    .unwrap()
+                    .messages()
+                    .map(|(id, record)| {
+                        (
+                            *id,
+                            record.talker(),
+                            record.reference(),
+                            record.text().to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(messages(rebuilt), messages(original));
+        }
+    }
+
+    #[test]
+    fn import_reports_unattributed_lines() {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let book = build_book(&fixture_game(), config).unwrap();
+
+        let unattributed = unattributed_lines(&book)
+            .into_iter()
+            .map(|(id, talker)| (id.room_num(), id.noun_num(), id.sequence_num(), talker))
+            .collect::<Vec<_>>();
+        assert_eq!(unattributed, vec![(100, 1, 2, 5)]);
+    }
+
+    #[test]
+    fn reimport_keeps_audio_status_of_unchanged_lines() {
+        let mut previous =
+            BookFile::from_book(&build_book(&fixture_game(), BookConfig::default()).unwrap());
+        let lines = &mut previous.rooms[0].nouns[0].conversations[0].lines;
+        lines[0].audio_status = Some(AudioStatus::Approved);
+        lines[1].audio_status = Some(AudioStatus::Recorded);
+        // The recording for this line was of different text.
+        lines[1].text = "Who is there?".to_string();
+
+        let mut builder = book_builder(&fixture_game(), BookConfig::default()).unwrap();
+        assert_eq!(restore_audio_statuses(&mut builder, &previous).unwrap(), 1);
+        let book = builder.build().unwrap();
+        let statuses = book
+            .lines()
+            .map(|line| (line.text().to_string(), line.audio_status()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                ("Hello?".to_string(), Some(AudioStatus::Approved)),
+                ("Who goes there?".to_string(), None),
+                ("A mop.".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn preview_wraps_line_text() {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let book_file = BookFile::from_book(&build_book(&fixture_game(), config).unwrap());
+
+        let mut out = Vec::new();
+        write_preview(&book_file, Some(100), 6, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "Room 100\n",
+                "  Noun 1, verb 2, condition 0:\n",
+                "    ego:\n",
+                "      Hello?\n",
+                "    Talker 5:\n",
+                "      Who\n",
+                "      goes\n",
+                "      there?\n",
+            )
+        );
+    }
+
+    #[test]
+    fn stats_report_totals_by_room_and_role() {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let book = build_book(&fixture_game(), config).unwrap();
+        let stats = BookStats::new(&book, 60);
+
+        assert_eq!((stats.lines, stats.words), (3, 6));
+        let rooms = stats
+            .rooms
+            .iter()
+            .map(|room| (room.room, room.conversations, room.lines))
+            .collect::<Vec<_>>();
+        assert_eq!(rooms, vec![(100, 1, 2), (200, 1, 1)]);
+        let [ego] = &stats.roles[..] else {
+            panic!("expected one role, got {:?}", stats.roles);
+        };
+        assert_eq!((ego.lines, ego.words, ego.duration_secs), (2, 3, 3.0));
+
+        let mut text = Vec::new();
+        stats.write_text(&mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.starts_with("Total: 3 lines, 6 words\n"), "{}", text);
+        assert!(text.contains("Roger Wilco"), "{}", text);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["lines"], 3);
+        assert_eq!(json["roles"][0]["role"], "ego");
+    }
+
+    #[test]
+    fn stats_from_book_file_match_built_book() {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let book = build_book(&fixture_game(), config).unwrap();
+        let built = BookStats::new(&book, 60);
+        let read = BookStats::from_book_file(&BookFile::from_book(&book), 60);
+
+        assert_eq!((read.lines, read.words), (built.lines, built.words));
+        let rooms = |stats: &BookStats| {
+            stats
+                .rooms
+                .iter()
+                .map(|room| (room.room, room.name.clone(), room.conversations, room.lines))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rooms(&read), rooms(&built));
+        let roles = |stats: &BookStats| {
+            stats
+                .roles
+                .iter()
+                .map(|role| {
+                    (
+                        role.role.clone(),
+                        role.lines,
+                        role.words,
+                        role.duration_secs,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(roles(&read), roles(&built));
+        // Role names are only in the config.
+        assert_eq!(read.roles[0].name, None);
+    }
+}
@@ -0,0 +1,196 @@
+//! Type-aware dumping of resource contents.
+//!
+//! Resources are decoded with the parser for their type where one exists,
+//! and fall back to a hex dump otherwise.
+
+use std::io::Write;
+
+use sci_resources::{
+    file::ResourceSet,
+    types::{msg::parse_message_resource, script::load_script, selector_table::SelectorTable},
+    ResourceId, ResourceType,
+};
+use sci_utils::{block::Block, buffer::Buffer, debug::hex_dump_to};
+
+/// The vocab resource number that holds the selector table.
+const SELECTOR_TABLE_VOCAB_NUM: u16 = 997;
+
+fn dump_messages<W: Write>(mut out: W, data: Block) -> anyhow::Result<()> {
+    let msg_resources = parse_message_resource(data)?;
+    writeln!(out, "noun verb cond  seq talker  text")?;
+    for (msg_id, record) in msg_resources.messages() {
+        let text = record
+            .text()
+            .replace("\r\n", "\n                              ");
+        writeln!(
+            out,
+            "{:>4} {:>4} {:>4} {:>4} {:>6}  {}",
+            msg_id.noun(),
+            msg_id.verb(),
+            msg_id.condition(),
+            msg_id.sequence(),
+            record.talker(),
+            text.trim()
+        )?;
+    }
+    Ok(())
+}
+
+fn dump_selectors<W: Write>(mut out: W, data: Block) -> anyhow::Result<()> {
+    let selector_table = SelectorTable::load_from(data.narrow())?;
+    for selector in selector_table.selectors() {
+        writeln!(out, "{:04X}: {}", selector.id(), selector.name())?;
+    }
+    Ok(())
+}
+
+fn dump_script<W: Write>(
+    mut out: W,
+    resource_set: &ResourceSet,
+    id: &ResourceId,
+    data: Block,
+) -> anyhow::Result<()> {
+    let load_data = |res_id: ResourceId| {
+        resource_set
+            .get_resource(&res_id)
+            .ok_or_else(|| anyhow::anyhow!("Resource not found: {:?}", res_id))?
+            .load_data()
+    };
+    let heap_data = load_data(ResourceId::new(ResourceType::Heap, id.resource_num()))?;
+    let loaded_script = load_script(&data.narrow(), &heap_data.narrow())?;
+    for object in loaded_script.objects() {
+        writeln!(
+            out,
+            "{} {}: species {:04X}, super {:04X}, {} properties, {} methods",
+            if object.is_class() { "Class" } else { "Object" },
+            object.name().unwrap_or("*NO NAME*"),
+            object.species(),
+            object.super_class(),
+            object.num_properties(),
+            object.num_methods(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a human readable dump of the resource to `out`.
+///
+/// If `raw` is set, or the resource type has no decoder, the resource is
+/// written as a hex dump. If decoding fails, a warning is printed to stderr
+/// and the hex dump is used instead.
+pub(super) fn dump_resource<W: Write>(
+    mut out: W,
+    resource_set: &ResourceSet,
+    id: &ResourceId,
+    raw: bool,
+) -> anyhow::Result<()> {
+    let res = resource_set
+        .get_resource(id)
+        .ok_or_else(|| anyhow::anyhow!("Resource not found: {:?}", id))?;
+    let data = res.load_data()?;
+
+    if !raw {
+        // Decode into a buffer first, so a failed decode doesn't leave partial
+        // output behind.
+        let mut decoded = Vec::new();
+        let result = match id.type_id() {
+            ResourceType::Message => Some(dump_messages(&mut decoded, data.clone())),
+            ResourceType::Vocab if id.resource_num() == SELECTOR_TABLE_VOCAB_NUM => {
+                Some(dump_selectors(&mut decoded, data.clone()))
+            }
+            ResourceType::Script => Some(dump_script(&mut decoded, resource_set, id, data.clone())),
+            _ => None,
+        };
+        match result {
+            Some(Ok(())) => {
+                out.write_all(&decoded)?;
+                return Ok(());
+            }
+            Some(Err(e)) => {
+                eprintln!("Failed to decode {:?}, falling back to hex dump: {}", id, e);
+                if let Some(location) = res.location() {
+                    eprintln!(
+                        "Resource is stored in {:?} at offset 0x{:X}",
+                        location.volume, location.offset
+                    );
+                    if let Some(packing) = location.packing {
+                        eprintln!(
+                            "Stored with compression type {}, {} bytes packed, {} bytes unpacked",
+                            packing.compression_type, packing.packed_size, packing.unpacked_size
+                        );
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    hex_dump_to(out, &data, 0)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::fixtures::{message_resource, resource_set};
+
+    use super::*;
+
+    fn dump_to_string(resource_set: &ResourceSet, id: &ResourceId, raw: bool) -> String {
+        let mut out = Vec::new();
+        dump_resource(&mut out, resource_set, id, raw).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn hex_dump_string(data: &[u8]) -> String {
+        let mut out = Vec::new();
+        hex_dump_to(&mut out, data, 0).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn message_resource_dumps_as_line_table() {
+        let msg_id = ResourceId::new(ResourceType::Message, 100);
+        let set = resource_set(vec![(
+            msg_id,
+            message_resource(&[
+                (1, 2, 0, 1, 3, "Hello there."),
+                (1, 2, 0, 2, 4, "General Kenobi!"),
+            ]),
+        )]);
+
+        assert_eq!(
+            dump_to_string(&set, &msg_id, false),
+            "noun verb cond  seq talker  text\n\
+             \x20  1    2    0    1      3  Hello there.\n\
+             \x20  1    2    0    2      4  General Kenobi!\n"
+        );
+    }
+
+    #[test]
+    fn raw_flag_forces_hex_dump() {
+        let msg_id = ResourceId::new(ResourceType::Message, 100);
+        let data = message_resource(&[(1, 2, 0, 1, 3, "Hello there.")]);
+        let set = resource_set(vec![(msg_id, data.clone())]);
+
+        assert_eq!(dump_to_string(&set, &msg_id, true), hex_dump_string(&data));
+    }
+
+    #[test]
+    fn resource_without_decoder_dumps_as_hex() {
+        let pic_id = ResourceId::new(ResourceType::Pic, 5);
+        let data = (0u8..40).collect::<Vec<_>>();
+        let set = resource_set(vec![(pic_id, data.clone())]);
+
+        assert_eq!(dump_to_string(&set, &pic_id, false), hex_dump_string(&data));
+    }
+
+    #[test]
+    fn undecodable_resource_falls_back_to_hex() {
+        let msg_id = ResourceId::new(ResourceType::Message, 100);
+        // An unsupported message resource version.
+        let data = vec![0xB8, 0x0B, 0x00, 0x00, 0x00, 0x00];
+        let set = resource_set(vec![(msg_id, data.clone())]);
+
+        assert_eq!(dump_to_string(&set, &msg_id, false), hex_dump_string(&data));
+    }
+}
@@ -0,0 +1,45 @@
+//! Helpers for building synthetic game resources in tests.
+
+use std::collections::BTreeMap;
+
+use sci_resources::{file::ResourceSet, ResourceId};
+use sci_utils::block::{Block, LazyBlock};
+
+/// Builds a version 4 message resource from (noun, verb, condition,
+/// sequence, talker, text) entries.
+pub fn message_resource(entries: &[(u8, u8, u8, u8, u8, &str)]) -> Vec<u8> {
+    const RECORD_SIZE: usize = 11;
+    let mut data = Vec::new();
+    data.extend_from_slice(&4000u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+
+    let mut strings = Vec::new();
+    let strings_start = data.len() + entries.len() * RECORD_SIZE;
+    for &(noun, verb, condition, sequence, talker, text) in entries {
+        let text_offset = (strings_start + strings.len()) as u16;
+        strings.extend_from_slice(text.as_bytes());
+        strings.push(0);
+
+        data.extend_from_slice(&[noun, verb, condition, sequence, talker]);
+        data.extend_from_slice(&text_offset.to_le_bytes());
+        data.extend_from_slice(&[noun, verb, condition, 0]);
+    }
+    data.extend(strings);
+    data
+}
+
+/// Builds an in-memory resource set from resource contents.
+pub fn resource_set(resources: Vec<(ResourceId, Vec<u8>)>) -> ResourceSet {
+    let entries = resources
+        .into_iter()
+        .map(|(id, data)| {
+            let block = Block::from_vec(data);
+            (id, LazyBlock::from_factory(move || Ok(block.clone())))
+        })
+        .collect::<BTreeMap<_, _>>();
+    ResourceSet {
+        entries,
+        locations: BTreeMap::new(),
+    }
+}
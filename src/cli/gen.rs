@@ -5,7 +5,12 @@ use clap::{Parser, Subcommand};
 use sci_resources::{file::open_game_resources, types::msg::parse_message_resource, ResourceType};
 
 use crate::{
-    book::{builder::BookBuilder, config::BookConfig, Book},
+    book::{
+        builder::BookBuilder,
+        config::BookConfig,
+        text::{parse_message_text, MessageSegment},
+        Book,
+    },
     gen::{
         doc::{
             text::{RichText, TextStyle},
@@ -21,49 +26,7 @@ struct CommonArgs {
     config_path: PathBuf,
 }
 
-enum MessageSegment<'a> {
-    Text(&'a str),
-    Control(char, Option<u32>),
-}
-
-fn split_first_char(text: &str) -> Option<(char, &str)> {
-    let mut chars = text.chars();
-    let first = chars.next()?;
-    Some((first, chars.as_str()))
-}
-
-fn parse_message_text(mut text: &str) -> Vec<MessageSegment> {
-    let mut segments = Vec::new();
-    loop {
-        let (next_text, control_start_rest) = match text.split_once('|') {
-            Some((first, second)) => (first, Some(second)),
-            None => (text, None),
-        };
-
-        if !next_text.is_empty() {
-            segments.push(MessageSegment::Text(next_text));
-        }
-
-        let Some(rest) = control_start_rest else {
-            break;
-        };
-        let (control, rest) = split_first_char(rest).unwrap();
-        let (value, rest) = rest.split_once('|').unwrap();
-        segments.push(MessageSegment::Control(
-            control,
-            if value.is_empty() {
-                None
-            } else {
-                Some(value.parse().unwrap())
-            },
-        ));
-        text = rest;
-    }
-
-    segments
-}
-
-fn convert_message_text_to_rich_text(ctxt: &str, text: &str) -> RichText {
+pub(super) fn convert_message_text_to_rich_text(ctxt: &str, text: &str) -> RichText {
     let segments = parse_message_text(text);
     let mut builder = RichText::builder();
     let mut curr_style = TextStyle::default();
@@ -177,52 +140,23 @@ fn load_book(args: &CommonArgs) -> anyhow::Result<Book> {
 }
 
 fn generate_conversation(mut section: SectionBuilder, conversation: &crate::book::Conversation) {
-    section.set_id(conversation_id_to_id_string(conversation.id()));
+    section.set_id(conversation.id().to_id_string());
     let mut content = section.add_content();
     let mut dialogue = content.add_dialogue();
     for line in conversation.lines() {
         dialogue.add_line(
             line.role().short_name(),
             convert_message_text_to_rich_text(&format!("{:?}", conversation.id()), line.text()),
-            line_id_to_id_string(line.id()),
+            line.id().to_id_string(),
         );
     }
 }
 
-fn room_id_to_id_string(room_id: crate::book::RoomId) -> String {
-    format!("room-{}", room_id.room_num())
-}
-
-fn noun_id_to_id_string(noun_id: crate::book::NounId) -> String {
-    format!("noun-{}-{}", noun_id.room_num(), noun_id.noun_num())
-}
-
-fn conversation_id_to_id_string(conversation_id: crate::book::ConversationId) -> String {
-    format!(
-        "conv-{}-{}-{}-{}",
-        conversation_id.room_num(),
-        conversation_id.noun_num(),
-        conversation_id.verb_num(),
-        conversation_id.condition_num(),
-    )
-}
-
-fn line_id_to_id_string(line_id: crate::book::LineId) -> String {
-    format!(
-        "line-{}-{}-{}-{}-{}",
-        line_id.room_num(),
-        line_id.noun_num(),
-        line_id.verb_num(),
-        line_id.condition_num(),
-        line_id.sequence_num(),
-    )
-}
-
 fn generate_document(book: &Book) -> anyhow::Result<Document> {
     let mut doc = DocumentBuilder::new(format!("{} Script", book.project_name()));
     for room in book.rooms() {
         let mut room_section = doc.add_chapter(room.name());
-        room_section.set_id(room_id_to_id_string(room.id()));
+        room_section.set_id(room.id().to_id_string());
         let mut room_section = room_section.into_section_builder();
 
         for noun in room.nouns() {
@@ -241,7 +175,7 @@ fn generate_document(book: &Book) -> anyhow::Result<Document> {
 
             let mut noun_section = room_section.add_subsection(noun_desc);
 
-            noun_section.set_id(noun_id_to_id_string(noun.id()));
+            noun_section.set_id(noun.id().to_id_string());
 
             match noun.conversations().exactly_one() {
                 Ok(conversation) => {
@@ -0,0 +1,290 @@
+//! Commands for inspecting script resources.
+
+use std::{fmt::Write as _, io::Write, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use itertools::Itertools;
+use sci_codegen::{
+    args::{ArgType, Signedness},
+    insts::InstBase,
+    opcode::Opcode,
+    pmachine::PMachineInst,
+};
+use sci_resources::{
+    file::open_game_resources,
+    types::{script::load_script, selector_table::SelectorTable},
+    ResourceId, ResourceType,
+};
+use sci_utils::buffer::Buffer;
+
+/// The vocab resource number that holds the selector table.
+const SELECTOR_TABLE_VOCAB_NUM: u16 = 997;
+
+/// Controls the extra columns printed in a disassembly listing.
+#[derive(Clone, Copy, Debug, Default)]
+struct ListingOptions {
+    offsets: bool,
+    raw: bool,
+}
+
+/// Formats the arguments of an instruction, showing signed arguments as
+/// signed values.
+fn format_args(inst: &PMachineInst) -> String {
+    inst.args_and_types()
+        .into_iter()
+        .map(|(arg, arg_type)| match arg_type {
+            ArgType::VarWord(Signedness::Signed) => (arg.value() as i16).to_string(),
+            _ => arg.value().to_string(),
+        })
+        .join(" ")
+}
+
+/// Writes a listing of the method starting at `start` in `code`.
+///
+/// Decoding stops at the first `ret` that no earlier branch jumps past.
+/// Branch targets are annotated with their absolute offsets, and `pushi`
+/// immediates with the selector of the same ID, if there is one.
+fn disassemble_method<W: Write>(
+    mut out: W,
+    code: &[u8],
+    start: usize,
+    selectors: &SelectorTable,
+    options: ListingOptions,
+) -> anyhow::Result<()> {
+    let mut offset = start;
+    let mut furthest_target = start;
+    loop {
+        anyhow::ensure!(
+            offset < code.len(),
+            "Method at {:04X} runs past the end of the script",
+            start
+        );
+        let mut reader = std::io::Cursor::new(&code[offset..]);
+        let inst = PMachineInst::decode(&mut reader).map_err(|e| {
+            anyhow::anyhow!("Failed to decode instruction at {:04X}: {}", offset, e)
+        })?;
+        let end = offset + reader.position() as usize;
+
+        let mut line = String::new();
+        if options.offsets {
+            write!(line, "{:04X}: ", offset)?;
+        }
+        if options.raw {
+            let bytes = code[offset..end]
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .join(" ");
+            write!(line, "{:<18}", bytes)?;
+        }
+        write!(
+            line,
+            "{} {}",
+            inst.opcode().opcode_name(),
+            format_args(&inst)
+        )?;
+
+        match &inst {
+            PMachineInst::BT(arg) | PMachineInst::BNT(arg) | PMachineInst::JMP(arg) => {
+                let target = (end as i64 + (arg.value() as i16) as i64) as usize;
+                furthest_target = furthest_target.max(target);
+                write!(line, " ; -> {:04X}", target)?;
+            }
+            PMachineInst::PUSHI(arg) => {
+                if let Some(selector) = selectors.get_selector_by_id(arg.value()) {
+                    write!(line, " ; {}", selector.name())?;
+                }
+            }
+            _ => {}
+        }
+        writeln!(out, "{}", line.trim_end())?;
+
+        if matches!(inst, PMachineInst::RET()) && furthest_target < end {
+            break;
+        }
+        offset = end;
+    }
+    Ok(())
+}
+
+#[derive(Parser)]
+struct Disassemble {
+    #[clap(index = 1)]
+    root_dir: PathBuf,
+    #[clap(index = 2)]
+    script_num: u16,
+    /// The method to disassemble, as `object.method`.
+    #[clap(index = 3)]
+    method: String,
+    /// Prefix each instruction with its offset in the loaded script.
+    #[clap(long, default_value = "false")]
+    offsets: bool,
+    /// Show the raw bytes of each instruction.
+    #[clap(long, default_value = "false")]
+    raw: bool,
+}
+
+impl Disassemble {
+    fn run(&self) -> anyhow::Result<()> {
+        let (object_name, method_name) = self
+            .method
+            .split_once('.')
+            .ok_or_else(|| anyhow::anyhow!("Expected object.method, got {:?}", self.method))?;
+
+        let resource_set = open_game_resources(&self.root_dir)?;
+        let load_data = |res_id: ResourceId| {
+            resource_set
+                .get_resource(&res_id)
+                .ok_or_else(|| anyhow::anyhow!("Resource not found: {:?}", res_id))?
+                .load_data()
+        };
+        let selector_table = SelectorTable::load_from(
+            load_data(ResourceId::new(
+                ResourceType::Vocab,
+                SELECTOR_TABLE_VOCAB_NUM,
+            ))?
+            .narrow(),
+        )?;
+        let script_data = load_data(ResourceId::new(ResourceType::Script, self.script_num))?;
+        let heap_data = load_data(ResourceId::new(ResourceType::Heap, self.script_num))?;
+        let loaded_script = load_script(&script_data.narrow(), &heap_data.narrow())?;
+
+        let object = loaded_script
+            .objects()
+            .iter()
+            .find(|object| object.name() == Some(object_name))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No object named {:?} in script {}",
+                    object_name,
+                    self.script_num
+                )
+            })?;
+        let selector = selector_table
+            .get_selector_by_name(method_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown selector: {:?}", method_name))?;
+        let method_offset = object.method_offset(selector.id()).ok_or_else(|| {
+            anyhow::anyhow!("{} does not define method {}", object_name, method_name)
+        })?;
+
+        disassemble_method(
+            std::io::stdout(),
+            loaded_script.data(),
+            method_offset as usize,
+            &selector_table,
+            ListingOptions {
+                offsets: self.offsets,
+                raw: self.raw,
+            },
+        )
+    }
+}
+
+#[derive(Subcommand)]
+enum ScriptCommand {
+    /// Disassembles a single method of an object in a script.
+    #[clap(name = "disasm")]
+    Disassemble(Disassemble),
+}
+
+#[derive(Parser)]
+pub struct Scripts {
+    #[clap(subcommand)]
+    script_cmd: ScriptCommand,
+}
+
+impl Scripts {
+    pub fn run(&self) -> anyhow::Result<()> {
+        match &self.script_cmd {
+            ScriptCommand::Disassemble(disasm) => disasm.run(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sci_codegen::args::Arg;
+
+    use super::*;
+
+    fn signed(value: i16) -> Arg {
+        Arg::new(ArgType::VarWord(Signedness::Signed), value as u16)
+    }
+
+    fn assemble(insts: &[PMachineInst]) -> Vec<u8> {
+        let mut code = Vec::new();
+        for inst in insts {
+            inst.encode(&mut code).unwrap();
+        }
+        code
+    }
+
+    fn listing(code: &[u8], start: usize, options: ListingOptions) -> Vec<String> {
+        let selectors = SelectorTable::from_names(&["x", "y", "init"]);
+        let mut out = Vec::new();
+        disassemble_method(&mut out, code, start, &selectors, options).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    // Two bytes of padding, then a method that returns 1 or 0 depending on
+    // the result of `(self init:)`, followed by an unrelated instruction.
+    fn method_code() -> Vec<u8> {
+        let mut code = vec![0, 0];
+        code.extend(assemble(&[
+            PMachineInst::PUSHI(signed(2)),
+            PMachineInst::PUSH0(),
+            PMachineInst::SELF(Arg::new(ArgType::Byte, 4)),
+            PMachineInst::BNT(signed(3)),
+            PMachineInst::LDI(signed(1)),
+            PMachineInst::RET(),
+            PMachineInst::LDI(signed(0)),
+            PMachineInst::RET(),
+            PMachineInst::PUSH1(),
+        ]));
+        code
+    }
+
+    #[test]
+    fn listing_follows_branches_past_early_returns() {
+        assert_eq!(
+            listing(&method_code(), 2, ListingOptions::default()),
+            vec![
+                "pushi 2 ; init",
+                "push0",
+                "self 4",
+                "bnt 3 ; -> 000C",
+                "ldi 1",
+                "ret",
+                "ldi 0",
+                "ret",
+            ]
+        );
+    }
+
+    #[test]
+    fn listing_can_show_offsets_and_raw_bytes() {
+        let lines = listing(
+            &method_code(),
+            2,
+            ListingOptions {
+                offsets: true,
+                raw: true,
+            },
+        );
+        assert_eq!(lines[0], "0002: 39 02             pushi 2 ; init");
+        assert_eq!(lines[3], "0007: 31 03             bnt 3 ; -> 000C");
+    }
+
+    #[test]
+    fn listing_fails_on_truncated_method() {
+        let code = assemble(&[PMachineInst::LDI(signed(1))]);
+        let selectors = SelectorTable::from_names(&["x"]);
+        assert!(
+            disassemble_method(Vec::new(), &code, 0, &selectors, ListingOptions::default())
+                .is_err()
+        );
+    }
+}
@@ -1 +1,107 @@
+use crate::gen::doc::text::RichText;
+
+pub mod book;
 pub mod msg;
+
+/// Renders the text as plain text, and wraps it into rows of at most `width`
+/// characters.
+///
+/// Rows are broken at whitespace where possible, and existing line breaks
+/// are preserved. A word longer than `width` is split across rows at
+/// character boundaries. A `width` of 0 disables wrapping, leaving one row
+/// per line of the text.
+pub fn wrap_rich_text(text: &RichText, width: usize) -> Vec<String> {
+    let plain_text = text
+        .items()
+        .iter()
+        .map(|item| item.text())
+        .collect::<String>();
+    if width == 0 {
+        return plain_text.lines().map(ToString::to_string).collect();
+    }
+
+    let mut rows = Vec::new();
+    for paragraph in plain_text.lines() {
+        let mut row = String::new();
+        let mut row_len = 0;
+        for word in paragraph.split_whitespace() {
+            let word_len = word.chars().count();
+            if row_len > 0 && row_len + 1 + word_len <= width {
+                row.push(' ');
+                row.push_str(word);
+                row_len += 1 + word_len;
+                continue;
+            }
+
+            if row_len > 0 {
+                rows.push(std::mem::take(&mut row));
+            }
+
+            let mut chars = word.chars().collect::<Vec<_>>();
+            while chars.len() > width {
+                rows.push(chars.drain(..width).collect());
+            }
+            row = chars.iter().collect();
+            row_len = chars.len();
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::doc::text::TextStyle;
+
+    use super::*;
+
+    #[test]
+    fn short_text_is_a_single_row() {
+        let text = RichText::from("Hello there.");
+        assert_eq!(wrap_rich_text(&text, 20), vec!["Hello there."]);
+    }
+
+    #[test]
+    fn long_text_wraps_at_word_boundaries() {
+        let mut builder = RichText::builder();
+        let mut italic = TextStyle::default();
+        italic.set_italic(true);
+        builder
+            .add_text("The quick brown ", &TextStyle::default())
+            .add_text("fox jumps over", &italic)
+            .add_text(" the lazy dog.", &TextStyle::default());
+        let text = builder.build();
+
+        assert_eq!(
+            wrap_rich_text(&text, 10),
+            vec!["The quick", "brown fox", "jumps over", "the lazy", "dog."]
+        );
+    }
+
+    #[test]
+    fn long_word_is_split_across_rows() {
+        let text = RichText::from("A supercalifragilistic día");
+        assert_eq!(
+            wrap_rich_text(&text, 8),
+            vec!["A", "supercal", "ifragili", "stic día"]
+        );
+    }
+
+    #[test]
+    fn zero_width_does_not_wrap() {
+        let text = RichText::from("A supercalifragilistic día\r\nSecond line");
+        assert_eq!(
+            wrap_rich_text(&text, 0),
+            vec!["A supercalifragilistic día", "Second line"]
+        );
+    }
+
+    #[test]
+    fn existing_line_breaks_are_preserved() {
+        let text = RichText::from("First line\r\n\r\nThird line");
+        assert_eq!(
+            wrap_rich_text(&text, 20),
+            vec!["First line", "", "Third line"]
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! The file format for books exported from scitool.
+
+use std::io;
+
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+
+use crate::book;
+
+/// The current version of the book file format.
+pub const BOOK_FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Line {
+    pub sequence: u8,
+    pub talker: u8,
+    /// The ID of the role speaking the line, or None if the talker is not
+    /// assigned to a role.
+    pub role: Option<String>,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_status: Option<book::AudioStatus>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conversation {
+    pub verb: u8,
+    pub condition: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub needs_review: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub play_order: Option<u32>,
+    pub lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Noun {
+    pub id: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    #[serde(default)]
+    pub is_cutscene: bool,
+    pub conversations: Vec<Conversation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Room {
+    pub id: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub nouns: Vec<Noun>,
+}
+
+impl Line {
+    fn from_book_line(line: &book::Line) -> Self {
+        Line {
+            sequence: line.id().sequence_num(),
+            talker: line.talker_num(),
+            role: line.try_role().map(|role| role.id().as_str().to_string()),
+            text: line.text().to_string(),
+            audio_status: line.audio_status(),
+        }
+    }
+}
+
+/// The top level structure for a book output file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookFile {
+    pub version: u32,
+    pub project_name: String,
+    pub rooms: Vec<Room>,
+}
+
+impl BookFile {
+    /// Serializes the book file in its canonical form: pretty-printed JSON
+    /// with a trailing newline, fields in declaration order, unset optional
+    /// fields omitted, and each list sorted by its ID. Saving a file loaded
+    /// from canonical output reproduces it byte for byte, so book files diff
+    /// cleanly under version control.
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn to_canonical_json(&self) -> serde_json::Result<String> {
+        let mut book_file = self.clone();
+        book_file.canonicalize();
+        let mut json = serde_json::to_string_pretty(&book_file)?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    fn canonicalize(&mut self) {
+        self.rooms.sort_by_key(|room| room.id);
+        for room in &mut self.rooms {
+            room.nouns.sort_by_key(|noun| noun.id);
+            for noun in &mut room.nouns {
+                noun.conversations
+                    .sort_by_key(|conversation| (conversation.verb, conversation.condition));
+                for conversation in &mut noun.conversations {
+                    conversation.lines.sort_by_key(|line| line.sequence);
+                }
+            }
+        }
+    }
+
+    #[cfg_attr(not(test), expect(dead_code))]
+    pub fn from_book(book: &book::Book) -> Self {
+        BookFile {
+            version: BOOK_FILE_VERSION,
+            project_name: book.project_name().to_string(),
+            rooms: book
+                .rooms()
+                .map(|room| Room {
+                    id: room.id().room_num(),
+                    name: room.try_name().map(ToString::to_string),
+                    nouns: room
+                        .nouns()
+                        .map(|noun| Noun {
+                            id: noun.id().noun_num(),
+                            desc: noun.desc().map(ToString::to_string),
+                            is_cutscene: noun.is_cutscene(),
+                            conversations: noun
+                                .conversations()
+                                .map(|conversation| Conversation {
+                                    verb: conversation.id().verb_num(),
+                                    condition: conversation.id().condition_num(),
+                                    note: conversation.note().map(ToString::to_string),
+                                    needs_review: conversation.needs_review(),
+                                    play_order: conversation.play_order(),
+                                    lines: conversation
+                                        .lines()
+                                        .map(|line| Line::from_book_line(&line))
+                                        .collect(),
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl book::Book {
+    /// Writes the book to `writer` in the canonical book file form (see
+    /// [`BookFile::to_canonical_json`]), serializing each entity as the book
+    /// is traversed instead of building a [`BookFile`] first.
+    pub fn write_export_json_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut serializer = serde_json::Serializer::pretty(&mut *writer);
+        BookRef(self).serialize(&mut serializer)?;
+        writer.write_all(b"\n")
+    }
+}
+
+/// Serializes the items of an iterator as a sequence. The iterator is
+/// created on demand, as serialization only has shared access.
+struct Seq<F>(F);
+
+impl<F, I> Serialize for Seq<F>
+where
+    F: Fn() -> I,
+    I: Iterator,
+    I::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq((self.0)())
+    }
+}
+
+// These mirror the field order and omitted fields of the owned structures
+// above, so the output matches theirs.
+
+struct BookRef<'a>(&'a book::Book);
+
+impl Serialize for BookRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("BookFile", 3)?;
+        state.serialize_field("version", &BOOK_FILE_VERSION)?;
+        state.serialize_field("project_name", self.0.project_name())?;
+        state.serialize_field("rooms", &Seq(|| self.0.rooms().map(RoomRef)))?;
+        state.end()
+    }
+}
+
+struct RoomRef<'a>(book::Room<'a>);
+
+impl Serialize for RoomRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Room", 3)?;
+        state.serialize_field("id", &self.0.id().room_num())?;
+        match self.0.try_name() {
+            Some(name) => state.serialize_field("name", name)?,
+            None => state.skip_field("name")?,
+        }
+        state.serialize_field("nouns", &Seq(|| self.0.nouns().map(NounRef)))?;
+        state.end()
+    }
+}
+
+struct NounRef<'a>(book::Noun<'a>);
+
+impl Serialize for NounRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Noun", 4)?;
+        state.serialize_field("id", &self.0.id().noun_num())?;
+        match self.0.desc() {
+            Some(desc) => state.serialize_field("desc", desc)?,
+            None => state.skip_field("desc")?,
+        }
+        state.serialize_field("is_cutscene", &self.0.is_cutscene())?;
+        state.serialize_field(
+            "conversations",
+            &Seq(|| self.0.conversations().map(ConversationRef)),
+        )?;
+        state.end()
+    }
+}
+
+struct ConversationRef<'a>(book::Conversation<'a>);
+
+impl Serialize for ConversationRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Conversation", 6)?;
+        state.serialize_field("verb", &self.0.id().verb_num())?;
+        state.serialize_field("condition", &self.0.id().condition_num())?;
+        match self.0.note() {
+            Some(note) => state.serialize_field("note", note)?,
+            None => state.skip_field("note")?,
+        }
+        state.serialize_field("needs_review", &self.0.needs_review())?;
+        match self.0.play_order() {
+            Some(play_order) => state.serialize_field("play_order", &play_order)?,
+            None => state.skip_field("play_order")?,
+        }
+        state.serialize_field(
+            "lines",
+            &Seq(|| self.0.lines().map(|line| Line::from_book_line(&line))),
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sci_resources::types::msg::parse_message_resource;
+    use sci_utils::block::Block;
+
+    use crate::{
+        book::{builder::BookBuilder, config::BookConfig, fixtures::CONFIG},
+        cli::fixtures::message_resource,
+    };
+
+    use super::*;
+
+    const CANONICAL: &str = r#"{
+  "version": 1,
+  "project_name": "Test",
+  "rooms": [
+    {
+      "id": 100,
+      "name": "Bridge",
+      "nouns": [
+        {
+          "id": 1,
+          "desc": "Captain's chair",
+          "is_cutscene": false,
+          "conversations": [
+            {
+              "verb": 2,
+              "condition": 3,
+              "note": "Check the timing",
+              "needs_review": false,
+              "lines": [
+                {
+                  "sequence": 1,
+                  "talker": 0,
+                  "role": "ego",
+                  "text": "Hello?"
+                },
+                {
+                  "sequence": 2,
+                  "talker": 0,
+                  "role": "ego",
+                  "text": "Anyone?"
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+  ]
+}
+"#;
+
+    fn book() -> book::Book {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        let messages = parse_message_resource(Block::from_vec(message_resource(&[
+            (1, 2, 3, 1, 0, "Hello?"),
+            (1, 2, 3, 2, 0, "Anyone?"),
+        ])))
+        .unwrap();
+        for (id, record) in messages.messages() {
+            builder.add_message(100, id, record).unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn saved_book_loads_to_same_structure() {
+        let book_file = BookFile::from_book(&book());
+        let json = book_file.to_canonical_json().unwrap();
+        let loaded: BookFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, book_file);
+        assert_eq!(json, CANONICAL);
+    }
+
+    /// Builds a book with the named room 100, and the unnamed room 200.
+    fn book_with_unnamed_room() -> book::Book {
+        let config: BookConfig = serde_yml::from_str(CONFIG).unwrap();
+        let mut builder = BookBuilder::new(config).unwrap();
+        let messages =
+            parse_message_resource(Block::from_vec(message_resource(&[(1, 2, 0, 1, 0, "Hi.")])))
+                .unwrap();
+        for room in [100, 200] {
+            for (id, record) in messages.messages() {
+                builder.add_message(room, id, record).unwrap();
+            }
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn unnamed_room_is_saved_without_name() {
+        let book_file = BookFile::from_book(&book_with_unnamed_room());
+
+        let room = book_file.rooms.iter().find(|room| room.id == 200).unwrap();
+        assert_eq!(room.name, None);
+        let json = book_file.to_canonical_json().unwrap();
+        assert!(!json.contains("NO NAME"), "{}", json);
+    }
+
+    #[test]
+    fn streamed_book_matches_canonical_json() {
+        let mut streamed = Vec::new();
+        book().write_export_json_to(&mut streamed).unwrap();
+        assert_eq!(String::from_utf8(streamed).unwrap(), CANONICAL);
+    }
+
+    #[test]
+    fn streamed_unnamed_room_matches_canonical_json() {
+        let book = book_with_unnamed_room();
+        let mut streamed = Vec::new();
+        book.write_export_json_to(&mut streamed).unwrap();
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            BookFile::from_book(&book).to_canonical_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical_input_saves_byte_for_byte() {
+        let loaded: BookFile = serde_json::from_str(CANONICAL).unwrap();
+        assert_eq!(loaded.to_canonical_json().unwrap(), CANONICAL);
+    }
+
+    #[test]
+    fn out_of_order_input_is_sorted_on_save() {
+        let mut book_file: BookFile = serde_json::from_str(CANONICAL).unwrap();
+        book_file.rooms[0].nouns[0].conversations[0].lines.reverse();
+        assert_eq!(book_file.to_canonical_json().unwrap(), CANONICAL);
+    }
+}